@@ -1,23 +1,126 @@
-use chrono::{DateTime, Datelike, Local, Timelike};
+use chrono::{DateTime, Local};
 use std::{
-    fs,
+    cell::Cell,
+    fmt,
+    path::{Path, PathBuf},
     time::{Duration, Instant},
 };
 
 // --- Import from our own internal config module ---
-use crate::config::{DEFAULT_SESSION_DURATION_MINS, MAX_RECENT_FILES, OUTPUT_DIR};
+use crate::browser::{Preview, SessionBrowser};
+use crate::clipboard;
+use crate::clock::SessionClock;
+use crate::config::{Config, ContinueMode, DEFAULT_WORD_GOAL, TimerEnd};
+use crate::daily;
+use crate::draft::{self, Draft};
+use crate::editor::{self, CursorMove};
+use crate::filter;
+use crate::history::History;
+use crate::layout::{self, TextLayout};
+use crate::metadata::{self, MetadataFormat, SessionEntry, SessionMetadata, SessionMode};
+use crate::naming::{self, NameFields, Subdirectories};
+use crate::search::{SearchIndex, SearchScreen};
+use crate::sprint::SprintSeries;
+use crate::stats::{self, Activity, ActivityStats, TextStats};
+use crate::storage::{self, DirStore, SessionStore, StorageError};
+use crate::viewer::SessionViewer;
 
 // --- Application States and Enums ---
 pub enum AppState {
     MainMenu,
     Freewrite,
     SessionEnd {
-        final_filename: String,
-        word_count: usize,
-        wpm: f64,
+        summary: SessionSummary,
+    },
+    /// Countdown between two sprints of `App::sprint`.
+    SprintBreak,
+    SprintSeriesEnd {
+        series: SprintSeries,
+    },
+    /// A saved session shown full screen, from `App::viewer`.
+    SessionViewer,
+    /// Every saved session, from `App::browser`.
+    SessionBrowser,
+    /// Search across all sessions, from `App::search`.
+    Search,
+    /// Typing the Recent panel's filter, `App::recent_filter`.
+    RecentFilter,
+    /// Choosing between viewing the saved session `name` and writing in it
+    /// again.
+    OpenSession {
+        name: String,
+    },
+    /// Confirming `action` on the session `name` selected in the browser, or
+    /// typing its new name or title into `App::session_action_input`.
+    SessionAction {
+        action: SessionAction,
+        name: String,
     },
     Quitting,
     CustomDurationInput,
+    CustomWordGoalInput,
+    Notice {
+        title: String,
+        messages: Vec<String>,
+    },
+    MigrationPrompt,
+    DraftRecovery,
+    QuitConfirm,
+    SaveError,
+    AlternatePathInput,
+}
+
+/// Something done to a saved session from the browser.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SessionAction {
+    Rename,
+    Retitle,
+    Duplicate,
+    /// Move to the trash.
+    Trash,
+    /// Move back out of the trash.
+    Restore,
+    /// Delete from the trash for good.
+    Purge,
+}
+
+impl SessionAction {
+    /// Whether the action asks for a new name or title rather than a yes or no.
+    pub fn takes_input(self) -> bool {
+        matches!(self, SessionAction::Rename | SessionAction::Retitle)
+    }
+
+    // Actions on sessions in the trash, the others are on saved sessions
+    fn in_trash(self) -> bool {
+        matches!(self, SessionAction::Restore | SessionAction::Purge)
+    }
+}
+
+/// What the session end screen shows about the session that just finished.
+pub struct SessionSummary {
+    pub final_filename: String,
+    pub word_count: usize,
+    pub wpm: f64,
+    /// Time actually spent writing, pauses excluded.
+    pub duration: Duration,
+    pub word_goal: Option<usize>,
+    /// Words written after the timer ran out, for sessions that went on.
+    pub overtime_words: Option<usize>,
+    /// Words written this time, for continued sessions.
+    pub words_added: Option<usize>,
+    /// Time from start to end, pauses included.
+    pub wall_duration: Duration,
+    pub text_stats: TextStats,
+    pub activity: ActivityStats,
+}
+
+/// A saved session being written in again, see `App::continue_session`.
+pub struct ContinuedSession {
+    /// Name of the session in the store.
+    pub name: String,
+    /// Its metadata when it was loaded, carried over when it is saved again.
+    pub metadata: Option<SessionMetadata>,
+    pub words_before: usize,
 }
 
 pub enum MenuItem {
@@ -25,26 +128,55 @@ pub enum MenuItem {
     Minute10,
     Minute20,
     Custom,
+    OpenEnded,
+    DefaultWordGoal,
+    CustomWordGoal,
+    SprintSeries,
 }
 
-impl MenuItem {
-    pub fn to_string(&self) -> String {
+impl fmt::Display for MenuItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
-            MenuItem::Minute5 => "5 minute session (Default)".to_string(),
-            MenuItem::Minute10 => "10 minute session".to_string(),
-            MenuItem::Minute20 => "20 minute session".to_string(),
-            MenuItem::Custom => "Custom duration".to_string(),
+            MenuItem::Minute5 => write!(f, "5 minute session (Default)"),
+            MenuItem::Minute10 => write!(f, "10 minute session"),
+            MenuItem::Minute20 => write!(f, "20 minute session"),
+            MenuItem::Custom => write!(f, "Custom duration"),
+            MenuItem::OpenEnded => write!(f, "Open-ended (stopwatch)"),
+            MenuItem::DefaultWordGoal => write!(f, "{} word goal", DEFAULT_WORD_GOAL),
+            MenuItem::CustomWordGoal => write!(f, "Custom word goal"),
+            MenuItem::SprintSeries => write!(f, "Sprint series"),
         }
     }
+}
 
+impl MenuItem {
     pub fn to_duration(&self) -> u64 {
         match self {
             MenuItem::Minute5 => 5,
             MenuItem::Minute10 => 10,
             MenuItem::Minute20 => 20,
-            MenuItem::Custom => 0,
+            MenuItem::Custom
+            | MenuItem::OpenEnded
+            | MenuItem::DefaultWordGoal
+            | MenuItem::CustomWordGoal
+            | MenuItem::SprintSeries => 0,
         }
     }
+
+    /// Target word count for word goal sessions, `None` for the others.
+    pub fn to_word_goal(&self) -> Option<WordGoal> {
+        match self {
+            MenuItem::DefaultWordGoal => Some(WordGoal::Fixed(DEFAULT_WORD_GOAL)),
+            MenuItem::CustomWordGoal => Some(WordGoal::Ask),
+            _ => None,
+        }
+    }
+}
+
+pub enum WordGoal {
+    Fixed(usize),
+    /// Typed in by the user before the session starts.
+    Ask,
 }
 
 pub enum PanelFocus {
@@ -55,28 +187,124 @@ pub enum PanelFocus {
 // --- Main Application Structure ---
 pub struct App {
     pub text: String,
-    pub start_time: Instant,
-    pub session_duration: Duration,
+    /// Byte offset of the cursor in `text`, always on a char boundary.
+    pub cursor: usize,
+    /// Column Up / Down aim for, kept while moving vertically.
+    pub cursor_goal_x: Option<u16>,
+    /// Width of the writing area at the last render, which the text is wrapped to.
+    pub writing_area_width: Cell<u16>,
+    /// Only allow typing at the end and deleting the last character.
+    pub append_only: bool,
+    /// Undo / redo of the current session's edits.
+    pub history: History,
+    /// Timer of the current session, which stops while the session is paused.
+    pub clock: SessionClock,
+    /// Wall clock time the current session started at, for its metadata.
+    pub session_started_at: DateTime<Local>,
+    /// Typing activity of the current session, for the summary's statistics.
+    pub activity: Activity,
+    /// When `update` last ran, used to tell how long the process was stopped.
+    pub last_tick: Instant,
+    /// How long the session runs, `None` for sessions that count up until
+    /// ended with Esc.
+    pub session_duration: Option<Duration>,
+    pub timer_end: TimerEnd,
+    /// Word count when the timer ran out, set once the session is in overtime.
+    pub words_at_time_up: Option<usize>,
     pub last_save_time: Instant,
     pub current_state: AppState,
 
     pub menu_items: Vec<MenuItem>,
     pub selected_menu_index: usize,
-    pub recent_files: Vec<String>,
+    pub recent_files: Vec<SessionEntry>,
+    /// Sessions saved this month and the words written in them.
+    pub month_totals: (usize, usize),
 
     pub selected_recent_index: usize,
+    /// Fuzzy filter of the Recent panel. While set, the panel lists every
+    /// session matching it rather than the latest ones.
+    pub recent_filter: Option<String>,
     pub panel_focus: PanelFocus,
     pub displayed_file_content: Option<String>,
+    pub displayed_file_metadata: Option<SessionMetadata>,
+    /// Set while a saved session is open in the viewer.
+    pub viewer: Option<SessionViewer>,
+    /// Set while the session browser is open, including while one of its
+    /// sessions is in the viewer.
+    pub browser: Option<SessionBrowser>,
+    /// Set while the search screen is open, including while one of its
+    /// results is in the viewer.
+    pub search: Option<SearchScreen>,
+    /// Full-text index of the sessions, built the first time search is used.
+    pub search_index: Option<SearchIndex>,
+    /// New name or title typed for a session, while renaming or retitling it.
+    pub session_action_input: String,
+    /// Why the last session action failed, shown until it is tried again.
+    pub session_action_error: Option<String>,
     pub custom_duration_input_text: String,
+    pub custom_word_goal_input_text: String,
+    /// Set for word goal sessions, which run until Esc instead of a timer.
+    pub word_goal: Option<usize>,
+    /// Set while a sprint series is running, from its first sprint to its last.
+    pub sprint: Option<SprintSeries>,
+    /// Set while writing in a saved session again.
+    pub continued: Option<ContinuedSession>,
+    /// Set while writing in a recovered draft, to the words it already had.
+    pub restored_draft: Option<usize>,
+    /// Set by the suspend key, for the main loop to stop the process.
+    pub suspend_requested: bool,
+
+    pub sessions_dir: PathBuf,
+    pub max_recent_files: usize,
+    /// Where sessions are saved to and listed from.
+    pub store: Box<dyn SessionStore>,
+    pub metadata_format: MetadataFormat,
+    pub filename_template: String,
+    pub subdirectories: Subdirectories,
+    pub daily_page: bool,
+    pub continue_mode: ContinueMode,
+    /// A `./freewrite_sessions` left behind by older versions, waiting to be moved.
+    pub legacy_sessions_dir: Option<PathBuf>,
+
+    pub default_session_duration_mins: u64,
+    pub autosave_interval: Option<Duration>,
+    /// Journal file the in-progress session is autosaved to.
+    pub draft_path: Option<PathBuf>,
+    pub autosave_error: Option<String>,
+    pub orphaned_drafts: Vec<Draft>,
+    pub selected_draft_index: usize,
+
+    /// Why the last attempt to save the session failed, while `SaveError` is shown.
+    pub save_error: Option<StorageError>,
+    pub save_error_status: Option<String>,
+    /// Set once ending the session failed to save. The timer then leaves it
+    /// to the user to try again, rather than retrying on every tick.
+    pub save_failed: bool,
+    pub alternate_path_input_text: String,
 }
 
 impl App {
-    pub fn new() -> Self {
+    pub fn new(config: &Config) -> Self {
+        let store = DirStore::open(&config.sessions_dir, config.metadata_format);
+        Self::with_store(config, Box::new(store))
+    }
+
+    pub fn with_store(config: &Config, store: Box<dyn SessionStore>) -> Self {
         let now = Instant::now();
-        Self {
+        let mut app = Self {
             text: String::new(),
-            start_time: now,
-            session_duration: Duration::from_secs(DEFAULT_SESSION_DURATION_MINS * 60),
+            cursor: 0,
+            cursor_goal_x: None,
+            writing_area_width: Cell::new(80),
+            append_only: config.append_only,
+            history: History::default(),
+            clock: SessionClock::start(),
+            session_started_at: Local::now(),
+            activity: Activity::default(),
+            last_tick: now,
+            session_duration: None,
+            timer_end: config.timer_end,
+            words_at_time_up: None,
             last_save_time: now,
             current_state: AppState::MainMenu,
 
@@ -85,15 +313,79 @@ impl App {
                 MenuItem::Minute10,
                 MenuItem::Minute20,
                 MenuItem::Custom,
+                MenuItem::OpenEnded,
+                MenuItem::DefaultWordGoal,
+                MenuItem::CustomWordGoal,
+                MenuItem::SprintSeries,
             ],
             selected_menu_index: 0,
-            recent_files: Self::load_recent_files(),
+            recent_files: Vec::new(),
+            month_totals: (0, 0),
 
             selected_recent_index: 0,
+            recent_filter: None,
             panel_focus: PanelFocus::Sessions,
             displayed_file_content: None,
+            displayed_file_metadata: None,
+            viewer: None,
+            browser: None,
+            search: None,
+            search_index: None,
+            session_action_input: String::new(),
+            session_action_error: None,
             custom_duration_input_text: String::new(),
+            custom_word_goal_input_text: String::new(),
+            word_goal: None,
+            sprint: None,
+            continued: None,
+
+            sessions_dir: config.sessions_dir.clone(),
+            max_recent_files: config.max_recent_files,
+            store,
+            metadata_format: config.metadata_format,
+            filename_template: config.filename_template.clone(),
+            subdirectories: config.subdirectories,
+            daily_page: config.daily_page,
+            continue_mode: config.continue_mode,
+            legacy_sessions_dir: None,
+            restored_draft: None,
+            suspend_requested: false,
+
+            default_session_duration_mins: config.default_session_duration_mins,
+            autosave_interval: match config.autosave_interval_secs {
+                0 => None,
+                secs => Some(Duration::from_secs(secs)),
+            },
+            draft_path: None,
+            autosave_error: None,
+            orphaned_drafts: Vec::new(),
+            selected_draft_index: 0,
+
+            save_error: None,
+            save_error_status: None,
+            save_failed: false,
+            alternate_path_input_text: String::new(),
+        };
+        app.refresh_sessions();
+        app.legacy_sessions_dir = storage::find_legacy_sessions_dir(&app.sessions_dir);
+        app.advance_startup();
+        app
+    }
+
+    /// Moves to the next pending startup prompt (legacy migration, then draft
+    /// recovery), or the main menu once there is nothing left to ask.
+    pub fn advance_startup(&mut self) {
+        if self.legacy_sessions_dir.is_some() {
+            self.current_state = AppState::MigrationPrompt;
+            return;
         }
+        self.orphaned_drafts = draft::list_orphaned(&self.sessions_dir);
+        self.selected_draft_index = 0;
+        self.current_state = if self.orphaned_drafts.is_empty() {
+            AppState::MainMenu
+        } else {
+            AppState::DraftRecovery
+        };
     }
 
     pub fn get_word_count(&self) -> usize {
@@ -103,100 +395,1512 @@ impl App {
             .count()
     }
 
-    fn save_text(&self) -> String {
-        let timestamp: DateTime<Local> = Local::now();
-        let filename = format!(
-            "{}/{:04}-{:02}-{:02}_{:02}:{:02}_freewritesession.txt",
-            OUTPUT_DIR,
-            timestamp.year(),
-            timestamp.month(),
-            timestamp.day(),
-            timestamp.hour(),
-            timestamp.minute()
-        );
+    /// Wraps the text to the writing area, as it is drawn on screen.
+    pub fn text_layout(&self) -> TextLayout {
+        TextLayout::new(&self.text, self.writing_area_width.get())
+    }
+
+    pub fn insert_char(&mut self, c: char) {
+        if self.append_only {
+            self.cursor = self.text.len();
+        }
+        self.replace_range(self.cursor, self.cursor, c.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Deletes the grapheme before the cursor, so an accented letter or an
+    /// emoji sequence goes away in one keypress.
+    pub fn delete_backward(&mut self) {
+        if self.append_only {
+            self.cursor = self.text.len();
+        }
+        if self.cursor > 0 {
+            let start = layout::prev_grapheme(&self.text, self.cursor);
+            self.replace_range(start, self.cursor, "");
+        }
+    }
+
+    pub fn delete_forward(&mut self) {
+        if !self.append_only && self.cursor < self.text.len() {
+            let end = layout::next_grapheme(&self.text, self.cursor);
+            self.replace_range(self.cursor, end, "");
+        }
+    }
+
+    // Every edit of the buffer goes through here so it lands in the undo history.
+    fn replace_range(&mut self, start: usize, end: usize, inserted: &str) {
+        self.cursor = self
+            .history
+            .replace_range(&mut self.text, start..end, inserted, self.cursor);
+        self.cursor_goal_x = None;
+        self.activity.record_edit(self.clock.active_elapsed());
+    }
 
-        fs::create_dir_all(OUTPUT_DIR).expect("Failed to create output directory");
-        fs::write(&filename, &self.text).expect("Failed to write to file");
-        filename
+    pub fn undo(&mut self) {
+        if let Some(cursor) = self.history.undo(&mut self.text) {
+            self.cursor = cursor;
+            self.cursor_goal_x = None;
+            self.activity.record_edit(self.clock.active_elapsed());
+        }
     }
 
-    fn load_recent_files() -> Vec<String> {
-        let mut files = Vec::new();
-        if let Ok(entries) = fs::read_dir(OUTPUT_DIR) {
-            let mut sorted_entries: Vec<_> = entries
-                .filter_map(|e| e.ok())
-                .filter(|e| e.file_type().map_or(false, |ft| ft.is_file()))
-                .collect();
+    pub fn redo(&mut self) {
+        if let Some(cursor) = self.history.redo(&mut self.text) {
+            self.cursor = cursor;
+            self.cursor_goal_x = None;
+            self.activity.record_edit(self.clock.active_elapsed());
+        }
+    }
 
-            sorted_entries.sort_by(|a, b| b.file_name().cmp(&a.file_name()));
+    pub fn move_cursor(&mut self, movement: CursorMove) {
+        self.history.seal();
+        if !self.append_only {
+            self.cursor = editor::move_cursor(
+                &self.text,
+                self.cursor,
+                movement,
+                &self.text_layout(),
+                &mut self.cursor_goal_x,
+            );
+        }
+    }
 
-            for entry in sorted_entries.into_iter().take(MAX_RECENT_FILES) {
-                if let Some(name) = entry.file_name().to_str() {
-                    files.push(name.to_string());
+    fn save_text(&mut self) -> Result<String, StorageError> {
+        let mut metadata = self.session_metadata();
+        let saved_at = Local::now();
+        let (name, text) = match (&self.sprint, &self.continued) {
+            (_, Some(continued)) => {
+                let name = match self.continue_mode {
+                    ContinueMode::Append => continued.name.clone(),
+                    ContinueMode::Version => {
+                        naming::unique_name(&continued.name, |name| self.store.exists(name))
+                    }
+                };
+                (name, self.text.clone())
+            }
+            // Sprints included, each is a session of the day
+            _ if self.daily_page => {
+                let name = naming::daily_page_name(self.subdirectories, self.session_started_at);
+                let (earlier, page) = if self.store.exists(&name) {
+                    self.store.load(&name)?
+                } else {
+                    (None, String::new())
+                };
+                // The whole page is written again, but the store replaces it
+                // atomically, so a failed save leaves the earlier sessions be
+                let page = daily::append(&page, self.session_started_at, &self.text);
+                // The page's metadata covers the whole day
+                metadata.mode = SessionMode::DailyPage;
+                metadata.word_count = daily::sessions(&page)
+                    .iter()
+                    .map(|session| session.word_count)
+                    .sum();
+                if let Some(earlier) = earlier {
+                    metadata.started_at = earlier.started_at.or(metadata.started_at);
+                    metadata.duration_secs += earlier.duration_secs;
+                    metadata.wall_duration_secs += earlier.wall_duration_secs;
+                    metadata.title = earlier.title;
+                }
+                metadata.wpm = stats::words_per_minute(
+                    metadata.word_count,
+                    Duration::from_secs(metadata.duration_secs),
+                );
+                (name, page)
+            }
+            (Some(series), _) if series.single_file => {
+                let text = format!("{}{}", series.separator(saved_at), self.text);
+                match &series.file {
+                    // The file is rewritten as a whole, so its metadata can
+                    // cover all the sprints in it
+                    Some(name) => {
+                        let (_, written) = self.store.load(name)?;
+                        (name.clone(), format!("{}{}", written, text))
+                    }
+                    None => (self.new_session_name(&text, saved_at, &metadata), text),
                 }
             }
+            _ => (
+                self.new_session_name(&self.text, saved_at, &metadata),
+                self.text.clone(),
+            ),
+        };
+        // Tags of every sprint in a shared file, not just the last one
+        metadata.tags = metadata::hashtags(&text);
+        self.store.save(&name, &text, &metadata)?;
+        if let Some(index) = &mut self.search_index {
+            index.insert(&name, metadata.ended_at, text);
+        }
+        if let Some(series) = &mut self.sprint
+            && series.single_file
+        {
+            series.file = Some(name.clone());
+        }
+        Ok(self.store.location(&name))
+    }
+
+    /// Metadata describing the current session as it is now. In a sprint
+    /// series sharing one file, it describes the whole series so far.
+    fn session_metadata(&self) -> SessionMetadata {
+        let word_count = self.get_word_count();
+        let duration = self.clock.active_elapsed();
+        let mut metadata = SessionMetadata {
+            version: metadata::METADATA_VERSION,
+            started_at: Some(self.session_started_at),
+            ended_at: Some(Local::now()),
+            mode: if self.sprint.is_some() {
+                SessionMode::Sprint
+            } else if self.word_goal.is_some() {
+                SessionMode::WordGoal
+            } else if self.session_duration.is_some() {
+                SessionMode::Timed
+            } else {
+                SessionMode::OpenEnded
+            },
+            duration_secs: duration.as_secs(),
+            wall_duration_secs: self.clock.wall_elapsed().as_secs(),
+            target_mins: self
+                .session_duration
+                .map(|duration| duration.as_secs() / 60),
+            word_goal: self.word_goal,
+            word_count,
+            wpm: stats::words_per_minute(word_count, duration),
+            prompt: None,
+            title: None,
+            tags: metadata::hashtags(&self.text),
+        };
+        // A continued session's file covers its earlier writing too
+        if let Some(ContinuedSession {
+            metadata: Some(earlier),
+            ..
+        }) = &self.continued
+        {
+            metadata.started_at = earlier.started_at.or(metadata.started_at);
+            metadata.duration_secs += earlier.duration_secs;
+            metadata.wall_duration_secs += earlier.wall_duration_secs;
+            metadata.wpm =
+                stats::words_per_minute(word_count, Duration::from_secs(metadata.duration_secs));
+            metadata.prompt = earlier.prompt.clone();
+            metadata.title = earlier.title.clone();
+        }
+        // How long a recovered draft was written for before is unknown
+        if self.restored_draft.is_some() {
+            metadata.mode = SessionMode::Draft;
+            metadata.wpm = 0.0;
         }
-        if files.is_empty() {
-            files.push("There's no recent session".to_string());
+        if let Some(series) = &self.sprint
+            && series.single_file
+        {
+            let total_words = series.total_words() + word_count;
+            let total_duration = series.total_duration() + duration;
+            metadata.started_at = Some(series.started_at);
+            metadata.wall_duration_secs =
+                (Local::now() - series.started_at).num_seconds().max(0) as u64;
+            metadata.word_count = total_words;
+            metadata.duration_secs = total_duration.as_secs();
+            metadata.wpm = stats::words_per_minute(total_words, total_duration);
         }
-        files
+        metadata
+    }
+
+    /// Name for a new session after the filename template, not taken by any
+    /// session in the store.
+    fn new_session_name(
+        &self,
+        text: &str,
+        saved_at: DateTime<Local>,
+        metadata: &SessionMetadata,
+    ) -> String {
+        let name = naming::session_name(
+            &self.filename_template,
+            self.subdirectories,
+            &NameFields {
+                saved_at,
+                text,
+                duration: Duration::from_secs(metadata.duration_secs),
+            },
+        );
+        naming::unique_name(&name, |name| self.store.exists(name))
+    }
+
+    /// Reloads the saved sessions behind the Recent panel and the totals.
+    fn refresh_sessions(&mut self) {
+        let mut sessions = self.store.list();
+        self.month_totals = stats::totals_since(&sessions, stats::start_of_month(Local::now()));
+        match self.recent_filter.as_deref() {
+            Some(query) if !query.is_empty() => {
+                sessions = filter::fuzzy_filter(sessions, query);
+            }
+            _ => sessions.truncate(self.max_recent_files),
+        }
+        self.recent_files = sessions;
+        self.selected_recent_index = self
+            .selected_recent_index
+            .min(self.recent_files.len().saturating_sub(1));
+    }
+
+    /// Starts typing a filter for the Recent panel, or edits the current one.
+    pub fn open_recent_filter(&mut self) {
+        self.recent_filter.get_or_insert_with(String::new);
+        self.panel_focus = PanelFocus::Recent;
+        self.current_state = AppState::RecentFilter;
+        self.load_recent_preview();
+    }
+
+    /// Narrows the Recent panel down to the sessions matching the filter
+    /// after it was edited, selecting the best match.
+    pub fn update_recent_filter(&mut self) {
+        self.selected_recent_index = 0;
+        self.refresh_sessions();
+        self.load_recent_preview();
+    }
+
+    /// Stops typing the filter, keeping the sessions it matched listed.
+    pub fn submit_recent_filter(&mut self) {
+        if self.recent_filter.as_ref().is_some_and(String::is_empty) {
+            self.recent_filter = None;
+        }
+        self.current_state = AppState::MainMenu;
+    }
+
+    /// Lists the latest sessions again.
+    pub fn clear_recent_filter(&mut self) {
+        self.recent_filter = None;
+        self.current_state = AppState::MainMenu;
+        self.update_recent_filter();
+    }
+
+    /// Moves the Recent panel's selection by `sessions`, down when positive,
+    /// wrapping around at either end.
+    pub fn select_recent_by(&mut self, sessions: isize) {
+        let len = self.recent_files.len();
+        if len > 0 {
+            self.selected_recent_index =
+                (self.selected_recent_index as isize + sessions).rem_euclid(len as isize) as usize;
+            self.load_recent_preview();
+        }
+    }
+
+    /// Shows the session selected in the Recent panel in the preview, or
+    /// nothing when no session is selected.
+    pub fn load_recent_preview(&mut self) {
+        match self.recent_files.get(self.selected_recent_index) {
+            Some(entry) if matches!(self.panel_focus, PanelFocus::Recent) => {
+                self.load_file_content_from_name(entry.name.clone());
+            }
+            _ => {
+                self.displayed_file_content = None;
+                self.displayed_file_metadata = None;
+            }
+        }
+    }
+
+    /// Reads the saved sessions again, e.g. after files were changed by hand.
+    pub fn reload_sessions(&mut self) {
+        if let Err(e) = self.store.refresh() {
+            self.show_notice("Sessions", vec![e.to_string()]);
+        }
+        // Built again from the reloaded sessions the next time it is needed
+        self.search_index = None;
+        self.refresh_sessions();
+        self.selected_recent_index = 0;
+        self.displayed_file_content = None;
+        self.displayed_file_metadata = None;
     }
 
     pub fn update(&mut self) {
+        self.last_tick = Instant::now();
+        if let AppState::SprintBreak = self.current_state
+            && self
+                .sprint
+                .as_ref()
+                .is_some_and(|series| series.break_time_left().is_zero())
+        {
+            self.start_next_sprint();
+        }
         if let AppState::Freewrite = self.current_state {
-            let elapsed_time = self.start_time.elapsed();
-
-            if elapsed_time >= self.session_duration {
-                self.end_session();
+            let text = &self.text;
+            // A continued session's activity only covers what is written now
+            let words_before = self.words_before().unwrap_or(0);
+            self.activity.tick(self.clock.active_elapsed(), || {
+                text.split_whitespace().count().saturating_sub(words_before)
+            });
+            if !self.save_failed && self.timer_expired() {
+                if let Err(e) = self.end_session() {
+                    self.show_save_error(e);
+                }
+            } else if self
+                .autosave_interval
+                .is_some_and(|interval| self.last_save_time.elapsed() >= interval)
+            {
+                self.autosave();
             }
         }
     }
 
+    // Whether the session has to end because its time is up. With a soft stop
+    // or a grace period the session goes into overtime instead, and the word
+    // count at that point is kept for the summary.
+    fn timer_expired(&mut self) -> bool {
+        let elapsed_time = self.clock.active_elapsed();
+        let Some(duration) = self.session_duration else {
+            return false;
+        };
+        if elapsed_time < duration {
+            return false;
+        }
+        if self.timer_end != TimerEnd::Hard && self.words_at_time_up.is_none() {
+            self.words_at_time_up = Some(self.get_word_count());
+        }
+        match self.timer_end {
+            TimerEnd::Hard => true,
+            TimerEnd::Soft => false,
+            TimerEnd::Grace(grace) => elapsed_time >= duration + grace,
+        }
+    }
+
+    /// Set once the timer has run out but the session goes on.
+    pub fn in_overtime(&self) -> bool {
+        self.words_at_time_up.is_some()
+    }
+
+    /// Writes the in-progress text to the session's draft file. Failures don't
+    /// interrupt writing, they are surfaced in the status line instead.
+    pub fn autosave(&mut self) {
+        self.last_save_time = Instant::now();
+        let Some(path) = &self.draft_path else {
+            return;
+        };
+        if self.text.is_empty() {
+            return;
+        }
+        self.autosave_error = draft::write_atomic(path, &self.text)
+            .err()
+            .map(|e| e.to_string());
+    }
+
+    /// Starts a timed session, or an open-ended one that counts up when
+    /// `duration_minutes` is 0.
     pub fn start_session(&mut self, duration_minutes: u64) {
         self.text = String::new();
-        self.start_time = Instant::now();
-        self.session_duration = Duration::from_secs(duration_minutes * 60);
+        self.cursor = 0;
+        self.history.clear();
+        self.clock = SessionClock::start();
+        self.session_started_at = Local::now();
+        self.activity = Activity::default();
+        self.session_duration =
+            (duration_minutes > 0).then(|| Duration::from_secs(duration_minutes * 60));
         self.last_save_time = Instant::now();
+        self.draft_path = Some(draft::draft_path(&self.sessions_dir, Local::now()));
+        self.autosave_error = None;
+        self.word_goal = None;
+        self.words_at_time_up = None;
+        self.save_failed = false;
+        self.sprint = None;
+        self.continued = None;
+        self.restored_draft = None;
         self.current_state = AppState::Freewrite;
     }
 
-    pub fn end_session(&mut self) {
-        let final_filename = self.save_text();
-        let word_count = self.get_word_count();
-        let actual_duration_secs = self.start_time.elapsed().as_secs();
-        let wpm = if actual_duration_secs > 0 {
-            (word_count as f64 / actual_duration_secs as f64) * 60.0
-        } else {
-            0.0
+    /// Asks whether to view the session selected in the Recent panel or to
+    /// continue writing it.
+    pub fn open_recent_selection(&mut self) {
+        if let Some(entry) = self.recent_files.get(self.selected_recent_index) {
+            self.current_state = AppState::OpenSession {
+                name: entry.name.clone(),
+            };
+        }
+    }
+
+    /// Writes in the saved session `name` again, with a new timer. When it
+    /// ends, it is saved over the original or next to it, after
+    /// `continue_mode`.
+    pub fn continue_session(&mut self, name: &str) {
+        if storage::untrashed_name(name).is_some() {
+            self.show_notice(
+                "Continue Writing",
+                vec!["Restore the session from the trash to write in it again.".to_string()],
+            );
+            return;
+        }
+        let (metadata, text) = match self.store.load(name) {
+            Ok(session) => session,
+            Err(e) => {
+                self.show_notice("Continue Writing", vec![e.to_string()]);
+                return;
+            }
         };
-        self.current_state = AppState::SessionEnd {
+        // Written as one session, a daily page would lose its headers' meaning
+        // and its metadata would no longer cover the day
+        let daily_page = match &metadata {
+            Some(metadata) => metadata.mode == SessionMode::DailyPage,
+            // Saved without metadata, only its headers tell a page apart
+            None => !daily::sessions(&text).is_empty(),
+        };
+        if daily_page {
+            self.show_notice(
+                "Continue Writing",
+                vec!["Daily pages are added to by writing a new session on that day.".to_string()],
+            );
+            return;
+        }
+        // Wherever it was opened from is left behind
+        self.viewer = None;
+        self.browser = None;
+        self.search = None;
+        self.start_session(self.default_session_duration_mins);
+        self.cursor = text.len();
+        self.continued = Some(ContinuedSession {
+            name: name.to_string(),
+            metadata,
+            words_before: text.split_whitespace().count(),
+        });
+        self.text = text;
+    }
+
+    // Words the session started out with, when it picks up earlier writing.
+    fn words_before(&self) -> Option<usize> {
+        self.continued
+            .as_ref()
+            .map(|session| session.words_before)
+            .or(self.restored_draft)
+    }
+
+    /// Starts an untimed session that is complete once `target` words are
+    /// written. It keeps going past the goal until ended with Esc.
+    pub fn start_word_goal_session(&mut self, target: usize) {
+        self.start_session(0);
+        self.word_goal = Some(target);
+    }
+
+    pub fn word_goal_reached(&self) -> bool {
+        self.word_goal
+            .is_some_and(|goal| self.get_word_count() >= goal)
+    }
+
+    /// Saves the session and moves to its summary. On failure nothing changes,
+    /// the text (and its draft) stay around so the caller can offer another way
+    /// to save it.
+    pub fn end_session(&mut self) -> Result<(), StorageError> {
+        let final_filename = self.save_text()?;
+        self.complete_session(final_filename);
+        Ok(())
+    }
+
+    /// Like `end_session`, but saves to a path picked by the user. A directory
+    /// gets the usual timestamped filename.
+    pub fn end_session_at(&mut self, path: &Path) -> Result<(), StorageError> {
+        let metadata = self.session_metadata();
+        let filename = storage::save_to_path(
+            path,
+            &self.filename_template,
+            &NameFields {
+                saved_at: Local::now(),
+                text: &self.text,
+                duration: self.clock.active_elapsed(),
+            },
+            &metadata,
+            self.metadata_format,
+        )?;
+        self.complete_session(filename.display().to_string());
+        Ok(())
+    }
+
+    fn complete_session(&mut self, final_filename: String) {
+        self.history.clear();
+        self.save_error = None;
+        self.save_error_status = None;
+        self.save_failed = false;
+        if let Some(path) = self.draft_path.take() {
+            let _ = draft::remove(&path);
+        }
+        let word_count = self.get_word_count();
+        let words_before = self.words_before();
+        self.continued = None;
+        self.restored_draft = None;
+        let words_written = word_count.saturating_sub(words_before.unwrap_or(0));
+        // Time spent paused doesn't count towards the WPM
+        let duration = self.clock.active_elapsed();
+        let wpm = stats::words_per_minute(words_written, duration);
+        let summary = SessionSummary {
             final_filename,
             word_count,
             wpm,
+            duration,
+            word_goal: self.word_goal,
+            overtime_words: self
+                .words_at_time_up
+                .map(|words| word_count.saturating_sub(words)),
+            words_added: words_before.map(|_| words_written),
+            wall_duration: self.clock.wall_elapsed(),
+            text_stats: TextStats::from_text(&self.text),
+            activity: self.activity.finish(duration, words_written),
         };
-        self.recent_files = App::load_recent_files();
+        self.refresh_sessions();
+
+        let Some(series) = &mut self.sprint else {
+            self.current_state = AppState::SessionEnd { summary };
+            return;
+        };
+        series.completed.push(summary);
+        if series.is_finished() {
+            self.end_sprint_series();
+        } else {
+            series.break_started = Instant::now();
+            self.current_state = AppState::SprintBreak;
+        }
+    }
+
+    pub fn start_sprint_series(&mut self, series: SprintSeries) {
+        self.sprint = Some(series);
+        self.start_next_sprint();
+    }
+
+    /// Starts the next sprint of the series, cutting the break short if needed.
+    pub fn start_next_sprint(&mut self) {
+        let Some(duration) = self
+            .sprint
+            .as_ref()
+            .map(|series| series.sprint_duration_mins)
+        else {
+            return;
+        };
+        // `start_session` forgets the series, it belongs to single sessions
+        let series = self.sprint.take();
+        self.start_session(duration);
+        self.sprint = series;
+    }
+
+    /// Stops the series after the sprints done so far and shows their summary.
+    pub fn end_sprint_series(&mut self) {
+        if let Some(series) = self.sprint.take() {
+            self.current_state = AppState::SprintSeriesEnd { series };
+        }
+    }
+
+    pub fn toggle_pause(&mut self) {
+        if self.clock.is_paused() {
+            self.clock.resume();
+        } else {
+            self.pause_session();
+        }
+    }
+
+    /// Stops the session timer, e.g. when the terminal loses focus. Only the
+    /// user resumes it, by typing or with the pause key.
+    pub fn pause_session(&mut self) {
+        if let AppState::Freewrite = self.current_state {
+            self.clock.pause();
+        }
+    }
+
+    /// Called after the process was stopped (job control) and continued. The
+    /// session counts as paused from the last tick before the stop.
+    pub fn pause_after_suspend(&mut self) {
+        if let AppState::Freewrite = self.current_state {
+            self.clock.pause_since(self.last_tick);
+        }
+    }
+
+    pub fn show_save_error(&mut self, error: StorageError) {
+        self.save_error = Some(error);
+        self.save_error_status = None;
+        self.save_failed = true;
+        self.current_state = AppState::SaveError;
+    }
+
+    pub fn retry_save(&mut self) {
+        if let Err(e) = self.end_session() {
+            self.show_save_error(e);
+        }
+    }
+
+    pub fn save_to_alternate_path(&mut self) {
+        let input = self.alternate_path_input_text.trim();
+        if input.is_empty() {
+            return;
+        }
+        let path = PathBuf::from(input);
+        match self.end_session_at(&path) {
+            Ok(()) => self.alternate_path_input_text.clear(),
+            Err(e) => self.show_save_error(e),
+        }
+    }
+
+    pub fn copy_text_to_clipboard(&mut self) {
+        self.save_error_status = Some(match clipboard::copy(&self.text) {
+            Ok(()) => "Copied the session to the clipboard.".to_string(),
+            Err(e) => format!("Failed to copy to the clipboard: {}", e),
+        });
+    }
+
+    /// Whether there is typed text that hasn't been saved to a session file yet.
+    pub fn has_unsaved_session(&self) -> bool {
+        matches!(
+            self.current_state,
+            AppState::Freewrite
+                | AppState::QuitConfirm
+                | AppState::SaveError
+                | AppState::AlternatePathInput
+        ) && !self.text.is_empty()
+    }
+
+    /// Saves the current session, then quits. If saving fails the app stays
+    /// open on the save error popup.
+    pub fn save_and_quit(&mut self) {
+        match self.end_session() {
+            Ok(()) => self.current_state = AppState::Quitting,
+            Err(e) => self.show_save_error(e),
+        }
+    }
+
+    /// Drops the current session and its draft, then quits.
+    pub fn discard_and_quit(&mut self) {
+        if let Some(path) = self.draft_path.take() {
+            let _ = draft::remove(&path);
+        }
+        self.text.clear();
+        self.cursor = 0;
+        self.current_state = AppState::Quitting;
+    }
+
+    /// Saves the current session if there is one, used when the app is asked
+    /// to exit without a chance to confirm (SIGTERM, SIGHUP).
+    pub fn save_before_exit(&mut self) {
+        if self.has_unsaved_session() && self.end_session().is_err() {
+            self.write_rescue_file();
+        }
+    }
+
+    /// Last resort after a crash: writes the unsaved text to the session's
+    /// draft, where it is picked up by draft recovery on the next launch.
+    pub fn write_rescue_file(&self) -> Option<PathBuf> {
+        if !self.has_unsaved_session() {
+            return None;
+        }
+        let path = self
+            .draft_path
+            .clone()
+            .unwrap_or_else(|| draft::draft_path(&self.sessions_dir, Local::now()));
+        draft::write_atomic(&path, &self.text).ok()?;
+        Some(path)
     }
 
     pub fn load_file_content_from_name(&mut self, filename: String) {
-        let file_path = std::path::Path::new(OUTPUT_DIR).join(filename.clone());
-        match fs::read_to_string(&file_path) {
-            Ok(content) => {
+        match self.store.load(&filename) {
+            Ok((metadata, content)) => {
                 self.displayed_file_content = Some(content);
+                self.displayed_file_metadata = metadata;
             }
             Err(e) => {
-                self.displayed_file_content =
-                    Some(format!("Error reading file {}: {}", filename, e));
+                self.displayed_file_content = Some(e.to_string());
+                self.displayed_file_metadata = None;
+            }
+        }
+    }
+
+    pub fn view_session(&mut self, name: String) {
+        match self.store.load(&name) {
+            Ok((metadata, text)) => {
+                self.viewer = Some(SessionViewer::new(name, text, metadata));
+                self.current_state = AppState::SessionViewer;
+            }
+            Err(e) => self.show_notice("Session Viewer", vec![e.to_string()]),
+        }
+    }
+
+    /// Leaves the viewer for wherever it was opened from.
+    pub fn close_viewer(&mut self) {
+        self.viewer = None;
+        self.current_state = if self.search.is_some() {
+            AppState::Search
+        } else if self.browser.is_some() {
+            AppState::SessionBrowser
+        } else {
+            AppState::MainMenu
+        };
+    }
+
+    pub fn open_search(&mut self) {
+        if self.search_index.is_none() {
+            self.search_index = Some(SearchIndex::build(self.store.as_ref()));
+        }
+        self.search = Some(SearchScreen::default());
+        self.current_state = AppState::Search;
+    }
+
+    pub fn close_search(&mut self) {
+        self.search = None;
+        self.current_state = AppState::MainMenu;
+    }
+
+    /// Runs the search again after the query or its mode changed.
+    pub fn update_search(&mut self) {
+        if let (Some(search), Some(index)) = (&mut self.search, &self.search_index) {
+            search.update(index);
+        }
+    }
+
+    /// Opens the selected search result in the viewer, at its first match.
+    pub fn view_search_selection(&mut self) {
+        let Some(search) = &self.search else {
+            return;
+        };
+        let Some(hit) = search.selected() else {
+            return;
+        };
+        let (name, query, matches) = (hit.name.clone(), search.query.clone(), hit.matches.clone());
+        self.view_session(name);
+        if let Some(viewer) = &mut self.viewer {
+            viewer.show_matches(query, matches);
+        }
+    }
+
+    pub fn open_browser(&mut self) {
+        self.browser = Some(SessionBrowser::new(self.store.list()));
+        self.current_state = AppState::SessionBrowser;
+        self.load_browser_preview();
+    }
+
+    pub fn close_browser(&mut self) {
+        self.browser = None;
+        self.current_state = AppState::MainMenu;
+        // The session it showed may have been renamed or deleted meanwhile
+        self.load_recent_preview();
+    }
+
+    /// Opens the session selected in the browser in the viewer.
+    pub fn view_browser_selection(&mut self) {
+        if let Some(name) = self
+            .browser
+            .as_ref()
+            .and_then(|browser| browser.selected())
+            .map(|entry| entry.name.clone())
+        {
+            self.view_session(name);
+        }
+    }
+
+    /// Reads the text of the session selected in the browser, unless it is
+    /// already shown. Only the selected session is ever read.
+    pub fn load_browser_preview(&mut self) {
+        let Some(browser) = &mut self.browser else {
+            return;
+        };
+        if !browser.preview_outdated() {
+            return;
+        }
+        browser.preview = browser.selected().map(|entry| {
+            let (metadata, text) = match self.store.load(&entry.name) {
+                Ok((metadata, text)) => (metadata.or_else(|| entry.metadata.clone()), text),
+                Err(e) => (entry.metadata.clone(), e.to_string()),
+            };
+            Preview {
+                name: entry.name.clone(),
+                metadata,
+                page_sessions: daily::sessions(&text),
+                text,
+            }
+        });
+    }
+
+    /// Lists the trash in the browser instead of the saved sessions, or the
+    /// other way around.
+    pub fn toggle_browser_trash(&mut self) {
+        if let Some(browser) = &mut self.browser {
+            let trash = !browser.trash;
+            browser.switch_list(
+                trash,
+                if trash {
+                    self.store.list_trash()
+                } else {
+                    self.store.list()
+                },
+            );
+        }
+        self.load_browser_preview();
+    }
+
+    // Lists the sessions in the browser again after they changed, and the
+    // Recent panel with them.
+    fn refresh_browser(&mut self) {
+        self.refresh_sessions();
+        if let Some(browser) = &mut self.browser {
+            browser.set_sessions(if browser.trash {
+                self.store.list_trash()
+            } else {
+                self.store.list()
+            });
+            browser.preview = None;
+        }
+        self.load_browser_preview();
+    }
+
+    /// Asks to confirm `action` on the session selected in the browser, or
+    /// for its new name or title.
+    pub fn open_session_action(&mut self, action: SessionAction) {
+        let Some(browser) = &self.browser else {
+            return;
+        };
+        let Some(entry) = browser.selected() else {
+            return;
+        };
+        if action.in_trash() != browser.trash {
+            return;
+        }
+        self.session_action_input = match action {
+            SessionAction::Rename => entry.name.clone(),
+            SessionAction::Retitle => entry
+                .metadata
+                .as_ref()
+                .and_then(|metadata| metadata.title.clone())
+                .unwrap_or_default(),
+            _ => String::new(),
+        };
+        self.session_action_error = None;
+        self.current_state = AppState::SessionAction {
+            action,
+            name: entry.name.clone(),
+        };
+    }
+
+    pub fn cancel_session_action(&mut self) {
+        self.session_action_input.clear();
+        self.session_action_error = None;
+        self.current_state = AppState::SessionBrowser;
+    }
+
+    /// Carries out the action being confirmed. If it fails, the popup stays
+    /// open with the reason.
+    pub fn confirm_session_action(&mut self) {
+        let AppState::SessionAction { action, name } = &self.current_state else {
+            return;
+        };
+        let (action, name) = (*action, name.clone());
+        let input = self.session_action_input.clone();
+        let result = match action {
+            SessionAction::Rename => naming::parse_session_name(&input)
+                .and_then(|new_name| self.rename_session(&name, &new_name)),
+            SessionAction::Retitle => self.retitle_session(&name, input.trim()),
+            SessionAction::Duplicate => self.duplicate_session(&name),
+            SessionAction::Trash => self.trash_session(&name),
+            SessionAction::Restore => self.restore_session(&name),
+            SessionAction::Purge => self.store.delete(&name).map_err(|e| e.to_string()),
+        };
+        match result {
+            Ok(()) => {
+                self.cancel_session_action();
+                self.refresh_browser();
             }
+            Err(e) => self.session_action_error = Some(e),
+        }
+    }
+
+    fn rename_session(&mut self, name: &str, new_name: &str) -> Result<(), String> {
+        if new_name == name {
+            return Ok(());
+        }
+        if self.store.exists(new_name) {
+            return Err(format!("There's already a session named {}", new_name));
+        }
+        self.store
+            .rename(name, new_name)
+            .map_err(|e| e.to_string())?;
+        if let Some(index) = &mut self.search_index {
+            index.rename(name, new_name);
+        }
+        Ok(())
+    }
+
+    // The title is kept in the metadata, so the session is saved again with it.
+    fn retitle_session(&mut self, name: &str, title: &str) -> Result<(), String> {
+        if self.metadata_format == MetadataFormat::Off {
+            return Err("Titles are kept in the session metadata, which is off".to_string());
+        }
+        let (metadata, text) = self.store.load(name).map_err(|e| e.to_string())?;
+        let mut metadata = metadata.unwrap_or_else(|| self.derived_metadata(name, &text));
+        metadata.title = (!title.is_empty()).then(|| title.to_string());
+        self.store
+            .save(name, &text, &metadata)
+            .map_err(|e| e.to_string())
+    }
+
+    // Metadata of a session saved without any, as listed by the store.
+    fn derived_metadata(&self, name: &str, text: &str) -> SessionMetadata {
+        self.store
+            .list()
+            .into_iter()
+            .find(|entry| entry.name == name)
+            .and_then(|entry| entry.metadata)
+            .unwrap_or_else(|| SessionMetadata {
+                version: metadata::METADATA_VERSION,
+                word_count: text.split_whitespace().count(),
+                tags: metadata::hashtags(text),
+                ..SessionMetadata::default()
+            })
+    }
+
+    // The copy is named after the original, with a number added.
+    fn duplicate_session(&mut self, name: &str) -> Result<(), String> {
+        let (metadata, text) = self.store.load(name).map_err(|e| e.to_string())?;
+        let metadata = metadata.unwrap_or_else(|| self.derived_metadata(name, &text));
+        let copy = naming::unique_name(name, |name| self.store.exists(name));
+        self.store
+            .save(&copy, &text, &metadata)
+            .map_err(|e| e.to_string())?;
+        if let Some(index) = &mut self.search_index {
+            index.insert(&copy, metadata.ended_at.or(metadata.started_at), text);
+        }
+        Ok(())
+    }
+
+    fn trash_session(&mut self, name: &str) -> Result<(), String> {
+        let trashed =
+            naming::unique_name(&storage::trash_name(name), |name| self.store.exists(name));
+        self.store
+            .rename(name, &trashed)
+            .map_err(|e| e.to_string())?;
+        if let Some(index) = &mut self.search_index {
+            index.remove(name);
+        }
+        Ok(())
+    }
+
+    // Back under the name it had, or a free variant of it.
+    fn restore_session(&mut self, trashed: &str) -> Result<(), String> {
+        let name = storage::untrashed_name(trashed).unwrap_or(trashed);
+        let name = naming::unique_name(name, |name| self.store.exists(name));
+        self.store
+            .rename(trashed, &name)
+            .map_err(|e| e.to_string())?;
+        if let Some(index) = &mut self.search_index
+            && let Ok((metadata, text)) = self.store.load(&name)
+        {
+            let saved_at = metadata.and_then(|m| m.ended_at.or(m.started_at));
+            index.insert(&name, saved_at, text);
+        }
+        Ok(())
+    }
+
+    /// Shows `messages` in a popup over the main menu.
+    pub fn show_notice(&mut self, title: &str, messages: Vec<String>) {
+        self.current_state = AppState::Notice {
+            title: title.to_string(),
+            messages,
+        };
+    }
+
+    /// Leaves a notice popup, continuing with any prompt still pending from
+    /// startup.
+    pub fn dismiss_notice(&mut self) {
+        self.current_state = if self.legacy_sessions_dir.is_some() {
+            AppState::MigrationPrompt
+        } else if !self.orphaned_drafts.is_empty() {
+            AppState::DraftRecovery
+        } else if self.search.is_some() {
+            AppState::Search
+        } else if self.browser.is_some() {
+            AppState::SessionBrowser
+        } else {
+            AppState::MainMenu
+        };
+    }
+
+    /// Moves every session from the legacy `./freewrite_sessions` into the
+    /// sessions directory. Files that can't be moved are left in place and
+    /// reported in a notice.
+    pub fn migrate_legacy_sessions(&mut self) {
+        let Some(legacy_dir) = self.legacy_sessions_dir.take() else {
+            return;
+        };
+        let (moved, mut errors) = storage::move_sessions(&legacy_dir, &self.sessions_dir);
+
+        let _ = self.store.refresh();
+        self.refresh_sessions();
+        if errors.is_empty() {
+            self.advance_startup();
+        } else {
+            errors.insert(
+                0,
+                format!(
+                    "Moved {} session(s) to {}, but some were left behind:",
+                    moved,
+                    self.sessions_dir.display()
+                ),
+            );
+            self.show_notice("Migration", errors);
+        }
+    }
+
+    /// Keeps the legacy directory where it is and remembers not to ask again.
+    pub fn decline_legacy_migration(&mut self) {
+        if let Some(legacy_dir) = self.legacy_sessions_dir.take() {
+            storage::decline_migration(&legacy_dir);
+        }
+        self.advance_startup();
+    }
+
+    /// Continues writing a recovered draft in a new session. The draft file is
+    /// reused so it keeps being autosaved until the session ends. The other
+    /// drafts stay on disk and are offered again on the next launch.
+    pub fn restore_draft(&mut self) {
+        if self.selected_draft_index >= self.orphaned_drafts.len() {
+            return;
+        }
+        let draft = self.orphaned_drafts.remove(self.selected_draft_index);
+        self.orphaned_drafts.clear();
+        // How long the session had left, or what it was aiming for, isn't
+        // kept with the draft, so it carries on as an open-ended one
+        self.start_session(0);
+        self.restored_draft = Some(draft.word_count());
+        self.text = draft.text;
+        self.cursor = self.text.len();
+        self.draft_path = Some(draft.path);
+    }
+
+    /// Saves a recovered draft as a regular session, named after when it was
+    /// started, and removes the draft.
+    pub fn save_draft(&mut self) {
+        let Some(draft) = self.orphaned_drafts.get(self.selected_draft_index) else {
+            return;
+        };
+        let metadata = SessionMetadata {
+            version: metadata::METADATA_VERSION,
+            started_at: Some(draft.started_at),
+            mode: SessionMode::Draft,
+            word_count: draft.word_count(),
+            tags: metadata::hashtags(&draft.text),
+            ..SessionMetadata::default()
+        };
+        let name = self.new_session_name(&draft.text, draft.started_at, &metadata);
+        if let Err(e) = self.store.save(&name, &draft.text, &metadata) {
+            self.show_notice("Draft Recovery", vec![e.to_string()]);
+            return;
+        }
+        if let Some(index) = &mut self.search_index {
+            index.insert(&name, metadata.started_at, draft.text.clone());
+        }
+        let _ = draft.discard();
+        self.orphaned_drafts.remove(self.selected_draft_index);
+        self.refresh_sessions();
+        self.after_draft_resolved();
+    }
+
+    pub fn discard_draft(&mut self) {
+        let Some(draft) = self.orphaned_drafts.get(self.selected_draft_index) else {
+            return;
+        };
+        if let Err(e) = draft.discard() {
+            self.show_notice(
+                "Draft Recovery",
+                vec![format!("Failed to delete {}: {}", draft.path.display(), e)],
+            );
+            return;
+        }
+        self.orphaned_drafts.remove(self.selected_draft_index);
+        self.after_draft_resolved();
+    }
+
+    fn after_draft_resolved(&mut self) {
+        if self.orphaned_drafts.is_empty() {
+            self.current_state = AppState::MainMenu;
+        } else {
+            self.selected_draft_index = self
+                .selected_draft_index
+                .min(self.orphaned_drafts.len() - 1);
         }
     }
 
     pub fn reset_to_main_menu(&mut self) {
         self.current_state = AppState::MainMenu;
         self.text = String::new();
-        self.recent_files = App::load_recent_files();
+        self.cursor = 0;
+        self.recent_filter = None;
+        self.refresh_sessions();
         self.displayed_file_content = None;
+        self.displayed_file_metadata = None;
         self.panel_focus = PanelFocus::Sessions;
         self.selected_menu_index = 0;
         self.selected_recent_index = 0;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStore;
+
+    fn app_with(config: Config) -> App {
+        app_with_store(config, MemoryStore::default())
+    }
+
+    fn app_with_store(config: Config, store: MemoryStore) -> App {
+        let config = Config {
+            sessions_dir: std::env::temp_dir().join("freewrite-tests-nonexistent"),
+            metadata_format: MetadataFormat::Off,
+            ..config
+        };
+        App::with_store(&config, Box::new(store))
+    }
+
+    fn write(app: &mut App, text: &str) {
+        text.chars().for_each(|c| app.insert_char(c));
+    }
+
+    /// Writes and saves a whole five minute session, back on the main menu.
+    fn save_session(app: &mut App, text: &str) {
+        app.start_session(5);
+        write(app, text);
+        app.end_session().unwrap();
+        app.reset_to_main_menu();
+    }
+
+    #[test]
+    fn ending_a_session_saves_it_to_the_store() {
+        let mut app = app_with(Config::default());
+        save_session(&mut app, "three little words");
+
+        assert_eq!(app.recent_files.len(), 1);
+        let (metadata, text) = app.store.load(&app.recent_files[0].name).unwrap();
+        assert_eq!(text, "three little words");
+        assert_eq!(metadata.unwrap().word_count, 3);
+        assert_eq!(app.month_totals, (1, 3));
+    }
+
+    #[test]
+    fn a_failed_save_when_time_is_up_is_left_to_the_user_to_retry() {
+        for timer_end in [TimerEnd::Hard, TimerEnd::Grace(Duration::from_secs(60))] {
+            let mut store = MemoryStore::default();
+            store.fail_saves = true;
+            let mut app = app_with_store(
+                Config {
+                    timer_end,
+                    ..Config::default()
+                },
+                store,
+            );
+            app.start_session(5);
+            write(&mut app, "words with nowhere to go");
+            app.clock = SessionClock::started_at(Instant::now() - Duration::from_secs(7 * 60));
+            app.update();
+            assert!(matches!(app.current_state, AppState::SaveError));
+
+            // Back to writing, as Esc does, and the next ticks leave it there
+            app.current_state = AppState::Freewrite;
+            app.update();
+            app.update();
+            assert!(matches!(app.current_state, AppState::Freewrite));
+
+            app.retry_save();
+            assert!(matches!(app.current_state, AppState::SaveError));
+            assert!(app.save_error.is_some());
+        }
+    }
+
+    #[test]
+    fn new_sessions_never_replace_existing_ones() {
+        let mut app = app_with(Config {
+            filename_template: "session".to_string(),
+            ..Config::default()
+        });
+        for text in ["first", "second"] {
+            save_session(&mut app, text);
+        }
+
+        let mut names: Vec<_> = app.recent_files.iter().map(|e| e.name.clone()).collect();
+        names.sort();
+        assert_eq!(names, ["session-2.txt", "session.txt"]);
+    }
+
+    #[test]
+    fn single_file_sprints_are_appended_to_one_session() {
+        let config = Config {
+            sprint_count: 2,
+            sprint_single_file: true,
+            ..Config::default()
+        };
+        let mut app = app_with(config.clone());
+        app.start_sprint_series(SprintSeries::new(&config));
+        write(&mut app, "one");
+        app.end_session().unwrap();
+        app.start_next_sprint();
+        write(&mut app, "two");
+        app.end_session().unwrap();
+
+        assert_eq!(app.recent_files.len(), 1);
+        let (metadata, text) = app.store.load(&app.recent_files[0].name).unwrap();
+        assert!(text.contains("one") && text.contains("two"));
+        assert_eq!(metadata.unwrap().word_count, 2);
+    }
+
+    #[test]
+    fn the_next_sprint_starts_once_the_break_is_over() {
+        let config = Config {
+            sprint_count: 2,
+            sprint_duration_mins: 20,
+            ..Config::default()
+        };
+        let mut app = app_with(config.clone());
+        app.start_sprint_series(SprintSeries::new(&config));
+        assert_eq!(app.session_duration, Some(Duration::from_secs(20 * 60)));
+        write(&mut app, "first sprint");
+        app.end_session().unwrap();
+        assert!(matches!(app.current_state, AppState::SprintBreak));
+
+        app.update();
+        assert!(matches!(app.current_state, AppState::SprintBreak));
+        let series = app.sprint.as_mut().unwrap();
+        series.break_started -= series.break_duration;
+        app.update();
+        assert!(matches!(app.current_state, AppState::Freewrite));
+        assert_eq!(app.text, "");
+        assert_eq!(app.session_duration, Some(Duration::from_secs(20 * 60)));
+        assert_eq!(app.sprint.as_ref().unwrap().current_sprint(), 2);
+
+        write(&mut app, "and the second");
+        app.end_session().unwrap();
+        let AppState::SprintSeriesEnd { series } = &app.current_state else {
+            panic!("the series didn't end");
+        };
+        assert_eq!(series.total_words(), 5);
+        assert!(app.sprint.is_none());
+    }
+
+    #[test]
+    fn the_recent_filter_matches_tags_and_first_lines() {
+        let mut app = app_with(Config::default());
+        for text in [
+            "Morning pages\nabout #travel",
+            "Dream journal\nflying again",
+        ] {
+            save_session(&mut app, text);
+        }
+        app.panel_focus = PanelFocus::Recent;
+        app.open_recent_filter();
+
+        let filtered = |app: &mut App, filter: &str| {
+            app.recent_filter = Some(filter.to_string());
+            app.update_recent_filter();
+            app.recent_files
+                .iter()
+                .map(|entry| entry.first_line.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(filtered(&mut app, "#trvl"), ["Morning pages"]);
+        assert_eq!(filtered(&mut app, "drmjrnl"), ["Dream journal"]);
+        assert!(filtered(&mut app, "nothing like it").is_empty());
+        assert_eq!(app.displayed_file_content, None);
+
+        filtered(&mut app, "dream");
+        assert_eq!(
+            app.displayed_file_content.as_deref(),
+            Some("Dream journal\nflying again")
+        );
+        app.clear_recent_filter();
+        assert_eq!(app.recent_files.len(), 2);
+    }
+
+    #[test]
+    fn trashed_sessions_can_be_restored_or_purged() {
+        let mut app = app_with(Config {
+            filename_template: "session".to_string(),
+            ..Config::default()
+        });
+        for text in ["keep me", "throw me away"] {
+            save_session(&mut app, text);
+        }
+        app.open_browser();
+        let act = |app: &mut App, action| {
+            app.open_session_action(action);
+            app.confirm_session_action();
+            assert_eq!(app.session_action_error, None);
+        };
+
+        act(&mut app, SessionAction::Trash);
+        assert_eq!(app.recent_files.len(), 1);
+        app.toggle_browser_trash();
+        let trashed = app
+            .browser
+            .as_ref()
+            .unwrap()
+            .selected()
+            .unwrap()
+            .name
+            .clone();
+        assert_eq!(storage::untrashed_name(&trashed), Some("session-2.txt"));
+
+        act(&mut app, SessionAction::Restore);
+        assert_eq!(app.recent_files.len(), 2);
+        assert!(app.store.list_trash().is_empty());
+
+        app.toggle_browser_trash();
+        act(&mut app, SessionAction::Trash);
+        app.toggle_browser_trash();
+        act(&mut app, SessionAction::Purge);
+        assert!(app.store.list_trash().is_empty());
+        assert_eq!(app.recent_files.len(), 1);
+    }
+
+    #[test]
+    fn continued_sessions_are_saved_over_or_next_to_the_original() {
+        for (continue_mode, sessions) in [(ContinueMode::Append, 1), (ContinueMode::Version, 2)] {
+            let mut app = app_with(Config {
+                continue_mode,
+                ..Config::default()
+            });
+            save_session(&mut app, "one two");
+
+            let name = app.recent_files[0].name.clone();
+            app.continue_session(&name);
+            write(&mut app, " three");
+            app.end_session().unwrap();
+            let AppState::SessionEnd { summary } = &app.current_state else {
+                panic!("the session didn't end");
+            };
+            assert_eq!(summary.words_added, Some(1));
+            assert_eq!(app.recent_files.len(), sessions);
+            let (metadata, text) = app.store.load(&app.recent_files[0].name).unwrap();
+            assert_eq!(text, "one two three");
+            assert_eq!(metadata.unwrap().word_count, 3);
+        }
+    }
+
+    #[test]
+    fn restoring_a_draft_ends_the_recovery_prompt() {
+        let mut app = app_with(Config::default());
+        let drafts_dir = draft::drafts_dir(&app.sessions_dir);
+        app.orphaned_drafts = ["first draft", "second draft here"]
+            .iter()
+            .enumerate()
+            .map(|(i, text)| Draft {
+                path: drafts_dir.join(format!("{}.draft", i)),
+                started_at: Local::now(),
+                text: text.to_string(),
+            })
+            .collect();
+        app.current_state = AppState::DraftRecovery;
+
+        app.restore_draft();
+        assert_eq!(app.text, "first draft");
+        assert_eq!(app.cursor, app.text.len());
+        assert_eq!(app.session_duration, None);
+        assert_eq!(app.word_goal, None);
+        assert!(app.sprint.is_none());
+        // Still going long after a default length session would have ended
+        app.clock = SessionClock::started_at(Instant::now() - Duration::from_secs(3600));
+        app.update();
+        assert!(matches!(app.current_state, AppState::Freewrite));
+
+        write(&mut app, " and more");
+        app.show_notice("Notice", Vec::new());
+        app.dismiss_notice();
+        assert!(matches!(app.current_state, AppState::MainMenu));
+
+        app.end_session().unwrap();
+        let AppState::SessionEnd { summary } = &app.current_state else {
+            panic!("the session didn't end");
+        };
+        assert_eq!(summary.words_added, Some(2));
+        let (metadata, text) = app.store.load(&app.recent_files[0].name).unwrap();
+        assert_eq!(text, "first draft and more");
+        let metadata = metadata.unwrap();
+        assert_eq!(metadata.mode, SessionMode::Draft);
+        assert_eq!(metadata.wpm, 0.0);
+    }
+
+    #[test]
+    fn a_continued_sessions_activity_leaves_out_the_earlier_words() {
+        let mut app = app_with(Config::default());
+        save_session(&mut app, "one two three four five six");
+
+        let name = app.recent_files[0].name.clone();
+        app.continue_session(&name);
+        write(&mut app, " seven eight");
+        // A minute and a half into the session
+        app.clock = SessionClock::started_at(Instant::now() - Duration::from_secs(90));
+        app.update();
+        write(&mut app, " nine");
+        app.end_session().unwrap();
+        let AppState::SessionEnd { summary } = &app.current_state else {
+            panic!("the session didn't end");
+        };
+        assert_eq!(summary.activity.words_per_minute, [2, 1]);
+        assert_eq!(summary.activity.peak_wpm, Some(2));
+        assert_eq!(summary.words_added, Some(3));
+    }
+
+    #[test]
+    fn daily_page_sessions_are_appended_to_the_page_of_the_day() {
+        let mut app = app_with(Config {
+            daily_page: true,
+            ..Config::default()
+        });
+        for text in ["morning words", "and evening ones too"] {
+            save_session(&mut app, text);
+        }
+
+        assert_eq!(app.recent_files.len(), 1);
+        let name = &app.recent_files[0].name;
+        assert_eq!(
+            *name,
+            format!("{}.txt", app.session_started_at.format("%Y-%m-%d"))
+        );
+        let (metadata, text) = app.store.load(name).unwrap();
+        let word_counts: Vec<_> = daily::sessions(&text)
+            .iter()
+            .map(|session| session.word_count)
+            .collect();
+        assert_eq!(word_counts, [2, 4]);
+        assert!(text.contains("morning words\n\n=== "));
+        let metadata = metadata.unwrap();
+        assert_eq!(metadata.mode, SessionMode::DailyPage);
+        assert_eq!(metadata.word_count, 6);
+
+        let name = name.clone();
+        app.continue_session(&name);
+        assert!(matches!(app.current_state, AppState::Notice { .. }));
+        assert!(app.continued.is_none());
+    }
+
+    #[test]
+    fn a_session_quoting_a_page_header_can_still_be_continued() {
+        let mut app = app_with(Config::default());
+        save_session(
+            &mut app,
+            "Notes\n=== 2025-06-01 09:00 ===\nlike a daily page",
+        );
+        let name = app.recent_files[0].name.clone();
+
+        app.continue_session(&name);
+        assert!(matches!(app.current_state, AppState::Freewrite));
+        assert!(app.continued.is_some());
+        assert!(app.text.contains("=== 2025-06-01 09:00 ==="));
+    }
+
+    #[test]
+    fn adding_to_a_daily_page_keeps_its_earlier_sessions_as_they_were() {
+        let mut app = app_with(Config {
+            daily_page: true,
+            ..Config::default()
+        });
+        let mut pages: Vec<String> = Vec::new();
+        for text in ["first thoughts  \n", "second", "third\n\n"] {
+            save_session(&mut app, text);
+            let (_, page) = app.store.load(&app.recent_files[0].name).unwrap();
+            if let Some(earlier) = pages.last() {
+                assert!(page.starts_with(earlier.as_str()));
+            }
+            pages.push(page);
+        }
+
+        let headers: Vec<&str> = pages[2]
+            .lines()
+            .filter(|line| line.starts_with("=== "))
+            .collect();
+        assert_eq!(
+            pages[2],
+            format!(
+                "{}\n\nfirst thoughts  \n\n{}\n\nsecond\n\n{}\n\nthird\n\n",
+                headers[0], headers[1], headers[2]
+            )
+        );
+    }
+}
@@ -0,0 +1,520 @@
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local};
+use ratatui::widgets::ListState;
+use std::{cell::RefCell, fmt};
+
+use crate::daily::PageSession;
+use crate::metadata::{SessionEntry, SessionMetadata};
+
+// Browser over every saved session, not just the few in the Recent panel.
+// Sessions are listed from the store's metadata alone; a session's text is
+// only read once it is selected, so directories with thousands of sessions
+// open instantly.
+//
+// The list is made of rows: a header for each day, week or month, followed by
+// its sessions. Only session rows can be selected.
+//
+// The browser also lists the trash, the same way, so sessions can be restored
+// or deleted for good.
+
+const PAGE_ROWS: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Grouping {
+    Day,
+    Week,
+    Month,
+}
+
+impl Grouping {
+    pub fn next(self) -> Self {
+        match self {
+            Grouping::Day => Grouping::Week,
+            Grouping::Week => Grouping::Month,
+            Grouping::Month => Grouping::Day,
+        }
+    }
+
+    // Label of the group `at` falls in. Weeks start on Monday.
+    fn label(self, at: DateTime<Local>) -> String {
+        match self {
+            Grouping::Day => at.format("%A, %Y-%m-%d").to_string(),
+            Grouping::Week => {
+                let monday = at.date_naive()
+                    - ChronoDuration::days(at.weekday().num_days_from_monday() as i64);
+                format!("Week of {}", monday.format("%Y-%m-%d"))
+            }
+            Grouping::Month => at.format("%B %Y").to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Grouping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Grouping::Day => write!(f, "day"),
+            Grouping::Week => write!(f, "week"),
+            Grouping::Month => write!(f, "month"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortBy {
+    Date,
+    Words,
+    Duration,
+}
+
+impl SortBy {
+    pub fn next(self) -> Self {
+        match self {
+            SortBy::Date => SortBy::Words,
+            SortBy::Words => SortBy::Duration,
+            SortBy::Duration => SortBy::Date,
+        }
+    }
+}
+
+impl fmt::Display for SortBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SortBy::Date => write!(f, "date"),
+            SortBy::Words => write!(f, "words"),
+            SortBy::Duration => write!(f, "duration"),
+        }
+    }
+}
+
+pub enum BrowserRow {
+    Header {
+        label: String,
+        sessions: usize,
+        words: usize,
+    },
+    /// Index into `SessionBrowser::sessions`.
+    Session(usize),
+}
+
+pub struct SessionBrowser {
+    pub sessions: Vec<SessionEntry>,
+    pub rows: Vec<BrowserRow>,
+    pub grouping: Grouping,
+    pub sort_by: SortBy,
+    /// Smallest first instead of largest (or oldest first for dates).
+    pub ascending: bool,
+    /// Whether `sessions` are the ones in the trash.
+    pub trash: bool,
+    /// Selected row and scroll offset, updated by the list when it is drawn.
+    pub list_state: RefCell<ListState>,
+    /// Text of the selected session, read when it was selected.
+    pub preview: Option<Preview>,
+}
+
+pub struct Preview {
+    pub name: String,
+    pub metadata: Option<SessionMetadata>,
+    pub text: String,
+    /// The sessions in it, when it is a daily page.
+    pub page_sessions: Vec<PageSession>,
+}
+
+impl SessionBrowser {
+    pub fn new(sessions: Vec<SessionEntry>) -> Self {
+        let mut browser = Self {
+            sessions,
+            rows: Vec::new(),
+            grouping: Grouping::Month,
+            sort_by: SortBy::Date,
+            ascending: false,
+            trash: false,
+            list_state: RefCell::new(ListState::default()),
+            preview: None,
+        };
+        browser.build_rows(None);
+        browser
+    }
+
+    /// Lists `sessions` instead, e.g. after one was renamed or deleted. The
+    /// selection stays on the same session, or the closest one if it is gone.
+    pub fn set_sessions(&mut self, sessions: Vec<SessionEntry>) {
+        let selected = self.selected_name();
+        self.sessions = sessions;
+        self.build_rows(selected);
+    }
+
+    /// Lists `sessions` from the top, e.g. the trash instead of the saved
+    /// sessions.
+    pub fn switch_list(&mut self, trash: bool, sessions: Vec<SessionEntry>) {
+        self.trash = trash;
+        self.sessions = sessions;
+        self.preview = None;
+        self.list_state.borrow_mut().select(None);
+        self.build_rows(None);
+    }
+
+    pub fn cycle_grouping(&mut self) {
+        let selected = self.selected_name();
+        self.grouping = self.grouping.next();
+        self.build_rows(selected);
+    }
+
+    pub fn cycle_sort(&mut self) {
+        let selected = self.selected_name();
+        self.sort_by = self.sort_by.next();
+        self.build_rows(selected);
+    }
+
+    pub fn reverse(&mut self) {
+        let selected = self.selected_name();
+        self.ascending = !self.ascending;
+        self.build_rows(selected);
+    }
+
+    // Groups the sessions by date, newest group first, and sorts each group.
+    // The selection stays on the session named `keep_selected` if given.
+    fn build_rows(&mut self, keep_selected: Option<String>) {
+        let previous_row = self.list_state.borrow().selected().unwrap_or(0);
+        let mut order: Vec<usize> = (0..self.sessions.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (a, b) = (&self.sessions[a], &self.sessions[b]);
+            let group = b
+                .saved_at()
+                .map(|at| self.group_key(at))
+                .cmp(&a.saved_at().map(|at| self.group_key(at)));
+            let key = match self.sort_by {
+                SortBy::Date => a.saved_at().cmp(&b.saved_at()),
+                SortBy::Words => word_count(a).cmp(&word_count(b)),
+                SortBy::Duration => duration_secs(a).cmp(&duration_secs(b)),
+            };
+            if self.ascending {
+                // Oldest first goes for the groups too
+                let group = if self.sort_by == SortBy::Date {
+                    group.reverse()
+                } else {
+                    group
+                };
+                group.then(key)
+            } else {
+                group.then(key.reverse())
+            }
+        });
+
+        self.rows.clear();
+        let mut current_group: Option<String> = None;
+        let mut header = 0;
+        for index in order {
+            let label = self.sessions[index]
+                .saved_at()
+                .map_or_else(|| "Undated".to_string(), |at| self.grouping.label(at));
+            if current_group.as_ref() != Some(&label) {
+                header = self.rows.len();
+                self.rows.push(BrowserRow::Header {
+                    label: label.clone(),
+                    sessions: 0,
+                    words: 0,
+                });
+                current_group = Some(label);
+            }
+            if let BrowserRow::Header {
+                sessions, words, ..
+            } = &mut self.rows[header]
+            {
+                *sessions += 1;
+                *words += word_count(&self.sessions[index]);
+            }
+            self.rows.push(BrowserRow::Session(index));
+        }
+
+        let selected = keep_selected
+            .and_then(|name| {
+                self.rows.iter().position(
+                    |row| matches!(row, BrowserRow::Session(i) if self.sessions[*i].name == name),
+                )
+            })
+            .or_else(|| self.session_row_from(previous_row, 1))
+            .or_else(|| self.session_row_from(previous_row, -1));
+        *self.list_state.borrow_mut() = ListState::default().with_selected(selected);
+    }
+
+    // Sorts groups chronologically, whatever they are labelled.
+    fn group_key(&self, at: DateTime<Local>) -> (i32, u32) {
+        match self.grouping {
+            Grouping::Day => (at.year(), at.ordinal()),
+            Grouping::Week => {
+                let week = at.iso_week();
+                (week.year(), week.week())
+            }
+            Grouping::Month => (at.year(), at.month()),
+        }
+    }
+
+    pub fn selected(&self) -> Option<&SessionEntry> {
+        match self.rows.get(self.list_state.borrow().selected()?)? {
+            BrowserRow::Session(index) => self.sessions.get(*index),
+            BrowserRow::Header { .. } => None,
+        }
+    }
+
+    fn selected_name(&self) -> Option<String> {
+        self.selected().map(|entry| entry.name.clone())
+    }
+
+    /// Moves the selection by `sessions` sessions, down when positive,
+    /// stepping over the group headers.
+    pub fn select_by(&mut self, sessions: isize) {
+        let Some(mut row) = self.list_state.borrow().selected() else {
+            return;
+        };
+        let step = sessions.signum();
+        for _ in 0..sessions.unsigned_abs() {
+            match self.session_row_from(row.saturating_add_signed(step), step) {
+                Some(next) if next != row => row = next,
+                _ => break,
+            }
+        }
+        self.list_state.borrow_mut().select(Some(row));
+    }
+
+    pub fn page_down(&mut self) {
+        self.select_by(PAGE_ROWS as isize);
+    }
+
+    pub fn page_up(&mut self) {
+        self.select_by(-(PAGE_ROWS as isize));
+    }
+
+    pub fn select_first(&mut self) {
+        let first = self.session_row_from(0, 1);
+        self.list_state.borrow_mut().select(first);
+    }
+
+    pub fn select_last(&mut self) {
+        let last = self.session_row_from(self.rows.len().saturating_sub(1), -1);
+        self.list_state.borrow_mut().select(last);
+    }
+
+    // The first session row at or after `row` in the direction of `step`.
+    fn session_row_from(&self, row: usize, step: isize) -> Option<usize> {
+        let mut row = row.min(self.rows.len().checked_sub(1)?);
+        loop {
+            if let BrowserRow::Session(_) = self.rows[row] {
+                return Some(row);
+            }
+            row = row
+                .checked_add_signed(step)
+                .filter(|r| *r < self.rows.len())?;
+        }
+    }
+
+    /// Whether the preview shows something else than the selected session.
+    pub fn preview_outdated(&self) -> bool {
+        self.selected().map(|entry| &entry.name) != self.preview.as_ref().map(|p| &p.name)
+    }
+}
+
+fn word_count(entry: &SessionEntry) -> usize {
+    entry.metadata.as_ref().map_or(0, |m| m.word_count)
+}
+
+fn duration_secs(entry: &SessionEntry) -> u64 {
+    entry.metadata.as_ref().map_or(0, |m| m.duration_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn entry(
+        name: &str,
+        saved_at: Option<(u32, u32, u32)>,
+        words: usize,
+        secs: u64,
+    ) -> SessionEntry {
+        SessionEntry {
+            name: name.to_string(),
+            metadata: Some(SessionMetadata {
+                ended_at: saved_at.map(|(month, day, hour)| {
+                    Local
+                        .with_ymd_and_hms(2025, month, day, hour, 0, 0)
+                        .unwrap()
+                }),
+                word_count: words,
+                duration_secs: secs,
+                ..SessionMetadata::default()
+            }),
+            first_line: String::new(),
+        }
+    }
+
+    // Two weeks, with two sessions on the Wednesday and one never dated
+    fn sessions() -> Vec<SessionEntry> {
+        vec![
+            entry("mon", Some((6, 2, 10)), 100, 600),
+            entry("wed-morning", Some((6, 4, 9)), 50, 1200),
+            entry("wed-evening", Some((6, 4, 18)), 300, 300),
+            entry("fri", Some((5, 30, 12)), 10, 60),
+            entry("undated", None, 5, 30),
+        ]
+    }
+
+    // Headers as `label: sessions/words`, sessions by name
+    fn rows(browser: &SessionBrowser) -> Vec<String> {
+        browser
+            .rows
+            .iter()
+            .map(|row| match row {
+                BrowserRow::Header {
+                    label,
+                    sessions,
+                    words,
+                } => format!("{}: {}/{}", label, sessions, words),
+                BrowserRow::Session(index) => browser.sessions[*index].name.clone(),
+            })
+            .collect()
+    }
+
+    fn selected(browser: &SessionBrowser) -> &str {
+        &browser.selected().unwrap().name
+    }
+
+    fn select(browser: &mut SessionBrowser, name: &str) {
+        browser.select_first();
+        while selected(browser) != name {
+            browser.select_by(1);
+        }
+    }
+
+    #[test]
+    fn sessions_are_grouped_by_month_week_or_day_with_totals() {
+        let mut browser = SessionBrowser::new(sessions());
+        assert_eq!(
+            rows(&browser),
+            [
+                "June 2025: 3/450",
+                "wed-evening",
+                "wed-morning",
+                "mon",
+                "May 2025: 1/10",
+                "fri",
+                "Undated: 1/5",
+                "undated",
+            ]
+        );
+
+        browser.cycle_grouping();
+        assert_eq!(browser.grouping, Grouping::Day);
+        assert_eq!(
+            rows(&browser),
+            [
+                "Wednesday, 2025-06-04: 2/350",
+                "wed-evening",
+                "wed-morning",
+                "Monday, 2025-06-02: 1/100",
+                "mon",
+                "Friday, 2025-05-30: 1/10",
+                "fri",
+                "Undated: 1/5",
+                "undated",
+            ]
+        );
+
+        browser.cycle_grouping();
+        assert_eq!(browser.grouping, Grouping::Week);
+        assert_eq!(
+            rows(&browser),
+            [
+                "Week of 2025-06-02: 3/450",
+                "wed-evening",
+                "wed-morning",
+                "mon",
+                "Week of 2025-05-26: 1/10",
+                "fri",
+                "Undated: 1/5",
+                "undated",
+            ]
+        );
+    }
+
+    #[test]
+    fn sessions_are_sorted_within_their_group_either_way() {
+        let mut browser = SessionBrowser::new(sessions());
+        let june = |browser: &SessionBrowser| rows(browser)[1..4].to_vec();
+        assert_eq!(june(&browser), ["wed-evening", "wed-morning", "mon"]);
+
+        browser.cycle_sort();
+        assert_eq!(browser.sort_by, SortBy::Words);
+        assert_eq!(june(&browser), ["wed-evening", "mon", "wed-morning"]);
+        browser.reverse();
+        assert_eq!(june(&browser), ["wed-morning", "mon", "wed-evening"]);
+        // Only oldest first reverses the groups
+        assert_eq!(rows(&browser)[0], "June 2025: 3/450");
+
+        browser.cycle_sort();
+        assert_eq!(browser.sort_by, SortBy::Duration);
+        assert_eq!(june(&browser), ["wed-evening", "mon", "wed-morning"]);
+        browser.reverse();
+        assert_eq!(june(&browser), ["wed-morning", "mon", "wed-evening"]);
+
+        browser.cycle_sort();
+        browser.reverse();
+        assert_eq!(browser.sort_by, SortBy::Date);
+        assert_eq!(
+            rows(&browser),
+            [
+                "Undated: 1/5",
+                "undated",
+                "May 2025: 1/10",
+                "fri",
+                "June 2025: 3/450",
+                "mon",
+                "wed-morning",
+                "wed-evening",
+            ]
+        );
+    }
+
+    #[test]
+    fn moving_the_selection_steps_over_headers() {
+        let mut browser = SessionBrowser::new(sessions());
+        assert_eq!(selected(&browser), "wed-evening");
+        browser.select_by(3);
+        assert_eq!(selected(&browser), "fri");
+        browser.select_by(1);
+        assert_eq!(selected(&browser), "undated");
+        browser.select_by(5);
+        assert_eq!(selected(&browser), "undated");
+        browser.select_by(-1);
+        assert_eq!(selected(&browser), "fri");
+        browser.page_up();
+        assert_eq!(selected(&browser), "wed-evening");
+        browser.select_last();
+        assert_eq!(selected(&browser), "undated");
+        browser.select_first();
+        assert_eq!(selected(&browser), "wed-evening");
+    }
+
+    #[test]
+    fn the_selection_follows_its_session_through_changes() {
+        let mut browser = SessionBrowser::new(sessions());
+        select(&mut browser, "wed-morning");
+        browser.reverse();
+        assert_eq!(selected(&browser), "wed-morning");
+        browser.reverse();
+        browser.cycle_sort();
+        assert_eq!(selected(&browser), "wed-morning");
+        browser.cycle_grouping();
+        assert_eq!(selected(&browser), "wed-morning");
+
+        let mut renamed = sessions();
+        renamed[0].name = "monday".to_string();
+        browser.set_sessions(renamed);
+        assert_eq!(selected(&browser), "wed-morning");
+
+        // Once it is gone, the session after it is selected instead
+        let mut deleted = sessions();
+        deleted.remove(1);
+        browser.set_sessions(deleted);
+        assert_eq!(selected(&browser), "mon");
+    }
+}
@@ -0,0 +1,121 @@
+use std::time::{Duration, Instant};
+
+// Session timer that can be paused. Wall time keeps running while paused, and
+// active time (what the session duration and WPM are measured in) doesn't.
+pub struct SessionClock {
+    started_at: Instant,
+    paused_at: Option<Instant>,
+    paused_total: Duration,
+}
+
+impl SessionClock {
+    pub fn start() -> Self {
+        Self::started_at(Instant::now())
+    }
+
+    /// A clock that has been running since `started_at`.
+    pub fn started_at(started_at: Instant) -> Self {
+        Self {
+            started_at,
+            paused_at: None,
+            paused_total: Duration::ZERO,
+        }
+    }
+
+    pub fn wall_elapsed(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    pub fn active_elapsed(&self) -> Duration {
+        let paused_now = self.paused_at.map_or(Duration::ZERO, |at| at.elapsed());
+        self.wall_elapsed()
+            .saturating_sub(self.paused_total + paused_now)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    pub fn pause(&mut self) {
+        self.pause_since(Instant::now());
+    }
+
+    /// Pauses as of `since`, for when the pause is only noticed after the fact
+    /// (e.g. the process was stopped and has just been continued).
+    pub fn pause_since(&mut self, since: Instant) {
+        if self.paused_at.is_none() {
+            self.paused_at = Some(since.max(self.started_at));
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if let Some(paused_at) = self.paused_at.take() {
+            self.paused_total += paused_at.elapsed();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs_ago(secs: u64) -> Instant {
+        Instant::now() - Duration::from_secs(secs)
+    }
+
+    // Time keeps running during the test, so allow for a little of it
+    fn assert_about(elapsed: Duration, secs: u64) {
+        let expected = Duration::from_secs(secs);
+        assert!(
+            elapsed.abs_diff(expected) < Duration::from_millis(500),
+            "{:?} isn't about {:?}",
+            elapsed,
+            expected
+        );
+    }
+
+    #[test]
+    fn a_pause_stops_active_time_but_not_wall_time() {
+        let mut clock = SessionClock::started_at(secs_ago(60));
+        clock.pause_since(secs_ago(40));
+        assert!(clock.is_paused());
+        assert_about(clock.active_elapsed(), 20);
+        assert_about(clock.wall_elapsed(), 60);
+    }
+
+    #[test]
+    fn active_time_leaves_out_every_pause_after_resuming() {
+        let mut clock = SessionClock::started_at(secs_ago(60));
+        clock.pause_since(secs_ago(40));
+        clock.resume();
+        assert!(!clock.is_paused());
+        assert_about(clock.active_elapsed(), 20);
+
+        clock.pause();
+        clock.resume();
+        assert_about(clock.active_elapsed(), 20);
+        assert_about(clock.wall_elapsed(), 60);
+    }
+
+    #[test]
+    fn pausing_again_while_paused_keeps_the_first_pause() {
+        let mut clock = SessionClock::started_at(secs_ago(60));
+        clock.pause_since(secs_ago(40));
+        clock.pause_since(secs_ago(10));
+        clock.pause();
+        assert_about(clock.active_elapsed(), 20);
+
+        clock.resume();
+        clock.resume();
+        assert_about(clock.active_elapsed(), 20);
+    }
+
+    #[test]
+    fn a_pause_noticed_late_never_starts_before_the_clock() {
+        let mut clock = SessionClock::started_at(secs_ago(10));
+        clock.pause_since(secs_ago(60));
+        assert_about(clock.active_elapsed(), 0);
+        clock.resume();
+        assert_about(clock.active_elapsed(), 0);
+    }
+}
@@ -1,11 +1,30 @@
 use ratatui::style::Color;
+use serde::Deserialize;
 
-// --- General Configuration ---
+use crate::metadata::MetadataFormat;
+use crate::naming::{self, DEFAULT_FILENAME_TEMPLATE, Subdirectories};
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+
+// --- Built-in Defaults ---
+// Used whenever the config file is missing, or a value in it is invalid.
 pub const DEFAULT_SESSION_DURATION_MINS: u64 = 5;
+pub const DEFAULT_WORD_GOAL: usize = 750;
 pub const OUTPUT_DIR: &str = "freewrite_sessions";
+pub const SESSIONS_DIR_ENV: &str = "FREEWRITE_DIR";
 pub const MAX_RECENT_FILES: usize = 10;
+pub const AUTOSAVE_INTERVAL_SECS: u64 = 10;
+pub const GRACE_PERIOD_SECS: u64 = 60;
+pub const SPRINT_COUNT: usize = 4;
+pub const SPRINT_DURATION_MINS: u64 = 25;
+pub const BREAK_DURATION_MINS: u64 = 5;
 
-// --- Color Palette ---
+// --- Default Color Palette ---
 pub const NORMAL_BORDER_COLOR: Color = Color::DarkGray;
 pub const FOCUS_BORDER_COLOR: Color = Color::LightBlue;
 pub const SELECTED_ITEM_BG: Color = Color::DarkGray;
@@ -15,3 +34,501 @@ pub const ACCENT_COLOR: Color = Color::LightCyan;
 pub const INFO_COLOR: Color = Color::Gray;
 pub const SUCCESS_COLOR: Color = Color::Green;
 pub const FILE_ITEM_COLOR: Color = Color::Green;
+
+const APP_DIR_NAME: &str = "freewrite-tui";
+const CONFIG_FILE_NAME: &str = "config.toml";
+const SESSIONS_DIR_NAME: &str = "sessions";
+
+// --- Command Line Arguments ---
+#[derive(Default, Debug)]
+pub struct CliArgs {
+    pub dir: Option<PathBuf>,
+    pub help: bool,
+}
+
+pub const USAGE: &str = "Usage: freewrite_tui [--dir <PATH>]
+
+Options:
+  --dir <PATH>  Directory to save and list sessions in (overrides $FREEWRITE_DIR and the config file)
+  -h, --help    Print this help";
+
+impl CliArgs {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, String> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "-h" | "--help" => parsed.help = true,
+                "--dir" => {
+                    let dir = args.next().ok_or("--dir requires a path")?;
+                    parsed.dir = Some(PathBuf::from(dir));
+                }
+                _ => match arg.strip_prefix("--dir=") {
+                    Some(dir) => parsed.dir = Some(PathBuf::from(dir)),
+                    None => return Err(format!("unexpected argument `{}`", arg)),
+                },
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+// --- Runtime Configuration ---
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub default_session_duration_mins: u64,
+    /// Absolute directory sessions are saved to and listed from.
+    pub sessions_dir: PathBuf,
+    pub max_recent_files: usize,
+    /// How often the in-progress session is written to its draft; 0 disables autosave.
+    pub autosave_interval_secs: u64,
+    /// Disables cursor movement in the writing area: text can only be added at
+    /// the end, and Backspace only removes the last character.
+    pub append_only: bool,
+    pub timer_end: TimerEnd,
+    /// Sprint series: how many sprints, and how long sprints and the breaks
+    /// between them last.
+    pub sprint_count: usize,
+    pub sprint_duration_mins: u64,
+    pub break_duration_mins: u64,
+    /// Append all sprints of a series to one file instead of one file each.
+    pub sprint_single_file: bool,
+    pub metadata_format: MetadataFormat,
+    /// Name of saved session files, see `naming`.
+    pub filename_template: String,
+    pub subdirectories: Subdirectories,
+    /// Append every session to one file per day instead of one file each,
+    /// see `daily`.
+    pub daily_page: bool,
+    pub continue_mode: ContinueMode,
+    pub theme: Theme,
+}
+
+/// What happens when the timer of a timed session runs out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimerEnd {
+    /// Save and end the session right away.
+    Hard,
+    /// Show that time is up, but keep writing until Esc.
+    Soft,
+    /// Keep writing for a while longer, then end the session.
+    Grace(Duration),
+}
+
+/// Where a continued session is saved once it ends.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContinueMode {
+    /// Over the session it was loaded from.
+    Append,
+    /// Next to it, as a new numbered version, leaving the original as it was.
+    Version,
+}
+
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub normal_border: Color,
+    pub focus_border: Color,
+    pub selected_item_bg: Color,
+    pub selected_item_fg: Color,
+    pub title: Color,
+    pub accent: Color,
+    pub info: Color,
+    pub success: Color,
+    pub file_item: Color,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            default_session_duration_mins: DEFAULT_SESSION_DURATION_MINS,
+            sessions_dir: default_sessions_dir(),
+            max_recent_files: MAX_RECENT_FILES,
+            autosave_interval_secs: AUTOSAVE_INTERVAL_SECS,
+            append_only: false,
+            timer_end: TimerEnd::Hard,
+            sprint_count: SPRINT_COUNT,
+            sprint_duration_mins: SPRINT_DURATION_MINS,
+            break_duration_mins: BREAK_DURATION_MINS,
+            sprint_single_file: false,
+            metadata_format: MetadataFormat::Sidecar,
+            filename_template: DEFAULT_FILENAME_TEMPLATE.to_string(),
+            subdirectories: Subdirectories::None,
+            daily_page: false,
+            continue_mode: ContinueMode::Append,
+            theme: Theme::default(),
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            normal_border: NORMAL_BORDER_COLOR,
+            focus_border: FOCUS_BORDER_COLOR,
+            selected_item_bg: SELECTED_ITEM_BG,
+            selected_item_fg: SELECTED_ITEM_FG,
+            title: TITLE_COLOR,
+            accent: ACCENT_COLOR,
+            info: INFO_COLOR,
+            success: SUCCESS_COLOR,
+            file_item: FILE_ITEM_COLOR,
+        }
+    }
+}
+
+// Mirrors the on-disk TOML layout. Every field is optional so a partial file
+// only overrides what it mentions.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ConfigFile {
+    default_session_duration_mins: Option<u64>,
+    output_dir: Option<String>,
+    max_recent_files: Option<usize>,
+    autosave_interval_secs: Option<u64>,
+    append_only: Option<bool>,
+    timer_end: Option<String>,
+    grace_period_secs: Option<u64>,
+    sprint_count: Option<usize>,
+    sprint_duration_mins: Option<u64>,
+    break_duration_mins: Option<u64>,
+    sprint_single_file: Option<bool>,
+    metadata: Option<String>,
+    filename_template: Option<String>,
+    subdirectories: Option<String>,
+    daily_page: Option<bool>,
+    continue_mode: Option<String>,
+    colors: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// Location of the config file: `$XDG_CONFIG_HOME/freewrite-tui/config.toml`,
+    /// falling back to `~/.config/freewrite-tui/config.toml`.
+    pub fn path() -> Option<PathBuf> {
+        let base = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(base.join(APP_DIR_NAME).join(CONFIG_FILE_NAME))
+    }
+
+    /// Loads the config file, falling back to defaults when it is missing.
+    /// Invalid values never abort startup: they are replaced by their default
+    /// and returned as human readable errors so the UI can show them.
+    ///
+    /// The sessions directory is taken from, in order of precedence, the
+    /// `--dir` flag, `$FREEWRITE_DIR`, `output_dir` in the file, and finally
+    /// the XDG data directory.
+    pub fn load(args: &CliArgs) -> (Self, Vec<String>) {
+        let (mut config, errors) = Self::load_file();
+        let env_dir = env::var_os(SESSIONS_DIR_ENV).filter(|dir| !dir.is_empty());
+        if let Some(dir) = args.dir.clone().or(env_dir.map(PathBuf::from)) {
+            config.sessions_dir = absolute_dir(&dir);
+        }
+        (config, errors)
+    }
+
+    fn load_file() -> (Self, Vec<String>) {
+        let Some(path) = Self::path() else {
+            return (Self::default(), Vec::new());
+        };
+        match fs::read_to_string(&path) {
+            Ok(contents) => {
+                let (config, mut errors) = Self::from_toml(&contents);
+                for error in errors.iter_mut() {
+                    *error = format!("{}: {}", path.display(), error);
+                }
+                (config, errors)
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (Self::default(), Vec::new()),
+            Err(e) => (
+                Self::default(),
+                vec![format!("Failed to read {}: {}", path.display(), e)],
+            ),
+        }
+    }
+
+    pub fn from_toml(contents: &str) -> (Self, Vec<String>) {
+        let mut config = Self::default();
+        let mut errors = Vec::new();
+
+        let file: ConfigFile = match toml::from_str(contents) {
+            Ok(file) => file,
+            Err(e) => {
+                errors.push(format!("invalid TOML, using defaults ({})", e.message()));
+                return (config, errors);
+            }
+        };
+
+        match file.default_session_duration_mins {
+            Some(0) => errors.push("default_session_duration_mins must be at least 1".to_string()),
+            Some(mins) => config.default_session_duration_mins = mins,
+            None => {}
+        }
+        match file.output_dir {
+            Some(dir) if dir.trim().is_empty() => {
+                errors.push("output_dir must not be empty".to_string())
+            }
+            Some(dir) => config.sessions_dir = absolute_dir(Path::new(&dir)),
+            None => {}
+        }
+        match file.max_recent_files {
+            Some(0) => errors.push("max_recent_files must be at least 1".to_string()),
+            Some(max) => config.max_recent_files = max,
+            None => {}
+        }
+        if let Some(secs) = file.autosave_interval_secs {
+            config.autosave_interval_secs = secs;
+        }
+        if let Some(append_only) = file.append_only {
+            config.append_only = append_only;
+        }
+        let grace_period = match file.grace_period_secs {
+            Some(0) => {
+                errors.push("grace_period_secs must be at least 1".to_string());
+                GRACE_PERIOD_SECS
+            }
+            Some(secs) => secs,
+            None => GRACE_PERIOD_SECS,
+        };
+        match file.timer_end.as_deref() {
+            Some("hard") | None => {}
+            Some("soft") => config.timer_end = TimerEnd::Soft,
+            Some("grace") => config.timer_end = TimerEnd::Grace(Duration::from_secs(grace_period)),
+            Some(other) => errors.push(format!(
+                "invalid timer_end `{}`, expected \"hard\", \"soft\" or \"grace\"",
+                other
+            )),
+        }
+        match file.sprint_count {
+            Some(0) => errors.push("sprint_count must be at least 1".to_string()),
+            Some(count) => config.sprint_count = count,
+            None => {}
+        }
+        match file.sprint_duration_mins {
+            Some(0) => errors.push("sprint_duration_mins must be at least 1".to_string()),
+            Some(mins) => config.sprint_duration_mins = mins,
+            None => {}
+        }
+        if let Some(mins) = file.break_duration_mins {
+            config.break_duration_mins = mins;
+        }
+        if let Some(single_file) = file.sprint_single_file {
+            config.sprint_single_file = single_file;
+        }
+        match file.metadata.as_deref() {
+            Some("sidecar") | None => {}
+            Some("frontmatter") => config.metadata_format = MetadataFormat::Frontmatter,
+            Some("off") => config.metadata_format = MetadataFormat::Off,
+            Some(other) => errors.push(format!(
+                "invalid metadata `{}`, expected \"sidecar\", \"frontmatter\" or \"off\"",
+                other
+            )),
+        }
+        if let Some(template) = file.filename_template {
+            match naming::validate_template(&template) {
+                Ok(()) => config.filename_template = template,
+                Err(e) => errors.push(e),
+            }
+        }
+        match file.subdirectories.as_deref() {
+            Some("none") | None => {}
+            Some("year") => config.subdirectories = Subdirectories::Year,
+            Some("month") => config.subdirectories = Subdirectories::Month,
+            Some(other) => errors.push(format!(
+                "invalid subdirectories `{}`, expected \"none\", \"year\" or \"month\"",
+                other
+            )),
+        }
+        if let Some(daily_page) = file.daily_page {
+            config.daily_page = daily_page;
+        }
+        match file.continue_mode.as_deref() {
+            Some("append") | None => {}
+            Some("version") => config.continue_mode = ContinueMode::Version,
+            Some(other) => errors.push(format!(
+                "invalid continue_mode `{}`, expected \"append\" or \"version\"",
+                other
+            )),
+        }
+
+        for (key, value) in &file.colors {
+            let slot = match key.as_str() {
+                "normal_border" => &mut config.theme.normal_border,
+                "focus_border" => &mut config.theme.focus_border,
+                "selected_item_bg" => &mut config.theme.selected_item_bg,
+                "selected_item_fg" => &mut config.theme.selected_item_fg,
+                "title" => &mut config.theme.title,
+                "accent" => &mut config.theme.accent,
+                "info" => &mut config.theme.info,
+                "success" => &mut config.theme.success,
+                "file_item" => &mut config.theme.file_item,
+                _ => {
+                    errors.push(format!("unknown color key `colors.{}`", key));
+                    continue;
+                }
+            };
+            match Color::from_str(value) {
+                Ok(color) => *slot = color,
+                Err(_) => errors.push(format!("invalid color `{}` for `colors.{}`", value, key)),
+            }
+        }
+
+        (config, errors)
+    }
+}
+
+/// `$XDG_DATA_HOME/freewrite-tui`, falling back to `~/.local/share/freewrite-tui`.
+pub fn data_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))?;
+    Some(base.join(APP_DIR_NAME))
+}
+
+fn default_sessions_dir() -> PathBuf {
+    match data_dir() {
+        Some(dir) => dir.join(SESSIONS_DIR_NAME),
+        None => absolute_dir(Path::new(OUTPUT_DIR)),
+    }
+}
+
+// Expands a leading `~` and anchors relative paths to the launch directory, so
+// the rest of the app only ever sees one absolute sessions directory.
+fn absolute_dir(dir: &Path) -> PathBuf {
+    let expanded = match (dir.strip_prefix("~"), env::var_os("HOME")) {
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => dir.to_path_buf(),
+    };
+    std::path::absolute(&expanded).unwrap_or(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_empty_file_gives_the_defaults() {
+        let (config, errors) = Config::from_toml("");
+        assert!(errors.is_empty());
+        let defaults = Config::default();
+        assert_eq!(
+            config.default_session_duration_mins,
+            DEFAULT_SESSION_DURATION_MINS
+        );
+        assert_eq!(config.sessions_dir, defaults.sessions_dir);
+        assert_eq!(config.max_recent_files, MAX_RECENT_FILES);
+        assert_eq!(config.autosave_interval_secs, AUTOSAVE_INTERVAL_SECS);
+        assert!(!config.append_only);
+        assert_eq!(config.timer_end, TimerEnd::Hard);
+        assert_eq!(config.sprint_count, SPRINT_COUNT);
+        assert_eq!(config.sprint_duration_mins, SPRINT_DURATION_MINS);
+        assert_eq!(config.break_duration_mins, BREAK_DURATION_MINS);
+        assert!(!config.sprint_single_file);
+        assert_eq!(config.metadata_format, MetadataFormat::Sidecar);
+        assert_eq!(config.filename_template, DEFAULT_FILENAME_TEMPLATE);
+        assert_eq!(config.subdirectories, Subdirectories::None);
+        assert!(!config.daily_page);
+        assert_eq!(config.continue_mode, ContinueMode::Append);
+        assert_eq!(config.theme.focus_border, FOCUS_BORDER_COLOR);
+    }
+
+    #[test]
+    fn a_full_file_sets_every_value() {
+        let (config, errors) = Config::from_toml(
+            r##"
+            default_session_duration_mins = 15
+            output_dir = "/tmp/freewrite"
+            max_recent_files = 20
+            autosave_interval_secs = 0
+            append_only = true
+            timer_end = "grace"
+            grace_period_secs = 90
+            sprint_count = 3
+            sprint_duration_mins = 20
+            break_duration_mins = 10
+            sprint_single_file = true
+            metadata = "frontmatter"
+            filename_template = "%Y-%m-%d_{slug}"
+            subdirectories = "month"
+            daily_page = true
+            continue_mode = "version"
+
+            [colors]
+            focus_border = "#89b4fa"
+            selected_item_bg = "dark-gray"
+            accent = "magenta"
+            "##,
+        );
+        assert_eq!(errors, Vec::<String>::new());
+        assert_eq!(config.default_session_duration_mins, 15);
+        assert_eq!(config.sessions_dir, PathBuf::from("/tmp/freewrite"));
+        assert_eq!(config.max_recent_files, 20);
+        assert_eq!(config.autosave_interval_secs, 0);
+        assert!(config.append_only);
+        assert_eq!(config.timer_end, TimerEnd::Grace(Duration::from_secs(90)));
+        assert_eq!(config.sprint_count, 3);
+        assert_eq!(config.sprint_duration_mins, 20);
+        assert_eq!(config.break_duration_mins, 10);
+        assert!(config.sprint_single_file);
+        assert_eq!(config.metadata_format, MetadataFormat::Frontmatter);
+        assert_eq!(config.filename_template, "%Y-%m-%d_{slug}");
+        assert_eq!(config.subdirectories, Subdirectories::Month);
+        assert!(config.daily_page);
+        assert_eq!(config.continue_mode, ContinueMode::Version);
+        assert_eq!(config.theme.focus_border, Color::Rgb(0x89, 0xb4, 0xfa));
+        assert_eq!(config.theme.selected_item_bg, Color::DarkGray);
+        assert_eq!(config.theme.accent, Color::Magenta);
+        assert_eq!(config.theme.title, TITLE_COLOR);
+    }
+
+    #[test]
+    fn invalid_values_are_reported_and_keep_their_default() {
+        let (config, errors) = Config::from_toml(
+            r#"
+            default_session_duration_mins = 0
+            max_recent_files = 0
+            timer_end = "gentle"
+            metadata = "yaml"
+            filename_template = "%Q"
+            subdirectories = "day"
+            continue_mode = "overwrite"
+
+            [colors]
+            accent = "not-a-color"
+            background = "black"
+            "#,
+        );
+        assert_eq!(errors.len(), 9, "{:#?}", errors);
+        assert!(errors.iter().any(|e| e.contains("timer_end `gentle`")));
+        assert!(errors.iter().any(|e| e.contains("`colors.background`")));
+        assert_eq!(
+            config.default_session_duration_mins,
+            DEFAULT_SESSION_DURATION_MINS
+        );
+        assert_eq!(config.max_recent_files, MAX_RECENT_FILES);
+        assert_eq!(config.timer_end, TimerEnd::Hard);
+        assert_eq!(config.metadata_format, MetadataFormat::Sidecar);
+        assert_eq!(config.filename_template, DEFAULT_FILENAME_TEMPLATE);
+        assert_eq!(config.subdirectories, Subdirectories::None);
+        assert_eq!(config.continue_mode, ContinueMode::Append);
+        assert_eq!(config.theme.accent, ACCENT_COLOR);
+    }
+
+    #[test]
+    fn a_file_that_isnt_toml_gives_the_defaults_and_an_error() {
+        let (config, errors) = Config::from_toml("max_recent_files = [");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].starts_with("invalid TOML"));
+        assert_eq!(config.max_recent_files, MAX_RECENT_FILES);
+
+        // A value of the wrong type makes the whole file invalid
+        let (config, errors) =
+            Config::from_toml("max_recent_files = \"ten\"\ndefault_session_duration_mins = 15");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            config.default_session_duration_mins,
+            DEFAULT_SESSION_DURATION_MINS
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use chrono::{DateTime, Local, NaiveDateTime};
+
+// Daily pages: with `daily_page` on, every session written on a day is
+// appended to one file named after the date, each under a header line with
+// when it was started. The headers are all that tells the sessions apart, so
+// they can be listed again from the text alone, whatever the metadata format.
+
+const HEADER_PREFIX: &str = "=== ";
+const HEADER_SUFFIX: &str = " ===";
+const HEADER_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// One session of a daily page.
+pub struct PageSession {
+    pub started_at: NaiveDateTime,
+    pub word_count: usize,
+}
+
+/// `page` with `text` added at the end as a session started at `started_at`.
+/// The page itself is kept as it is, only followed by a blank line if it
+/// doesn't end with one already.
+pub fn append(page: &str, started_at: DateTime<Local>, text: &str) -> String {
+    let header = format!(
+        "{}{}{}\n\n",
+        HEADER_PREFIX,
+        started_at.format(HEADER_FORMAT),
+        HEADER_SUFFIX
+    );
+    let separator = if page.is_empty() || page.ends_with("\n\n") {
+        ""
+    } else if page.ends_with('\n') {
+        "\n"
+    } else {
+        "\n\n"
+    };
+    format!("{}{}{}{}", page, separator, header, text)
+}
+
+/// The sessions of a daily page, in the order they were written. Text before
+/// the first header, e.g. added by hand, isn't part of any session.
+pub fn sessions(page: &str) -> Vec<PageSession> {
+    let mut headers = Vec::new();
+    let mut offset = 0;
+    for line in page.split_inclusive('\n') {
+        if let Some(started_at) = parse_header(line.trim_end()) {
+            headers.push((started_at, offset, offset + line.len()));
+        }
+        offset += line.len();
+    }
+    headers
+        .iter()
+        .enumerate()
+        .map(|(i, &(started_at, _, text_start))| {
+            let text_end = headers.get(i + 1).map_or(page.len(), |next| next.1);
+            PageSession {
+                started_at,
+                word_count: page[text_start..text_end].split_whitespace().count(),
+            }
+        })
+        .collect()
+}
+
+fn parse_header(line: &str) -> Option<NaiveDateTime> {
+    let timestamp = line
+        .strip_prefix(HEADER_PREFIX)?
+        .strip_suffix(HEADER_SUFFIX)?;
+    NaiveDateTime::parse_from_str(timestamp, HEADER_FORMAT).ok()
+}
@@ -0,0 +1,102 @@
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+// Drafts live in a hidden subdirectory of the sessions directory so they follow
+// `--dir` / `$FREEWRITE_DIR`, while staying out of the Recent panel (which only
+// lists files).
+const DRAFTS_DIR_NAME: &str = ".drafts";
+const DRAFT_EXTENSION: &str = "draft";
+const DRAFT_TIMESTAMP_FORMAT: &str = "%Y-%m-%d_%H-%M-%S";
+
+// --- Draft Files ---
+pub struct Draft {
+    pub path: PathBuf,
+    /// When the session that produced this draft was started.
+    pub started_at: DateTime<Local>,
+    pub text: String,
+}
+
+impl Draft {
+    pub fn word_count(&self) -> usize {
+        self.text.split_whitespace().count()
+    }
+
+    /// First non-empty line, used as a preview in the recovery prompt.
+    pub fn first_line(&self) -> &str {
+        self.text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or("")
+    }
+
+    pub fn discard(&self) -> io::Result<()> {
+        remove(&self.path)
+    }
+}
+
+pub fn drafts_dir(sessions_dir: &Path) -> PathBuf {
+    sessions_dir.join(DRAFTS_DIR_NAME)
+}
+
+/// Path of the draft for a session started at `started_at`.
+pub fn draft_path(sessions_dir: &Path, started_at: DateTime<Local>) -> PathBuf {
+    drafts_dir(sessions_dir).join(format!(
+        "{}.{}",
+        started_at.format(DRAFT_TIMESTAMP_FORMAT),
+        DRAFT_EXTENSION
+    ))
+}
+
+/// Drafts left behind by sessions that never reached `end_session`, oldest first.
+pub fn list_orphaned(sessions_dir: &Path) -> Vec<Draft> {
+    let Ok(entries) = fs::read_dir(drafts_dir(sessions_dir)) else {
+        return Vec::new();
+    };
+    let mut drafts: Vec<Draft> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == DRAFT_EXTENSION))
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_str()?;
+            let naive = NaiveDateTime::parse_from_str(stem, DRAFT_TIMESTAMP_FORMAT).ok()?;
+            let started_at = Local.from_local_datetime(&naive).earliest()?;
+            let text = fs::read_to_string(&path).ok()?;
+            Some(Draft {
+                path,
+                started_at,
+                text,
+            })
+        })
+        .collect();
+    drafts.sort_by_key(|draft| draft.started_at);
+    drafts
+}
+
+/// Writes `contents` to a temporary file next to `path`, syncs it and renames it
+/// into place, so a crash mid-write never leaves a truncated file behind.
+pub fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Hidden, so one left behind by a crash isn't listed as a session
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let tmp_path = path.with_file_name(format!(".{}.tmp", file_name));
+
+    let mut file = fs::File::create(&tmp_path)?;
+    file.write_all(contents.as_bytes())?;
+    file.sync_all()?;
+    fs::rename(&tmp_path, path)
+}
+
+/// Removes a draft, treating an already missing file as success.
+pub fn remove(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
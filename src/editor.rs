@@ -0,0 +1,144 @@
+use crate::layout::{self, TextLayout};
+
+// Cursor movement over the freewrite buffer. The cursor is a byte offset into
+// the text that always sits on a grapheme boundary; every function here takes
+// the current position and returns the new one, leaving the text untouched.
+
+pub enum CursorMove {
+    Left,
+    Right,
+    WordLeft,
+    WordRight,
+    Up,
+    Down,
+    LineStart,
+    LineEnd,
+    BufferStart,
+    BufferEnd,
+}
+
+/// Up / Down move between rows of `layout`, aiming for the column in `goal_x`
+/// so the cursor doesn't drift left when crossing shorter rows. Every other
+/// movement resets it.
+pub fn move_cursor(
+    text: &str,
+    cursor: usize,
+    movement: CursorMove,
+    layout: &TextLayout,
+    goal_x: &mut Option<u16>,
+) -> usize {
+    if !matches!(movement, CursorMove::Up | CursorMove::Down) {
+        *goal_x = None;
+    }
+    match movement {
+        CursorMove::Left => layout::prev_grapheme(text, cursor),
+        CursorMove::Right => layout::next_grapheme(text, cursor),
+        CursorMove::WordLeft => word_left(text, cursor),
+        CursorMove::WordRight => word_right(text, cursor),
+        CursorMove::Up => move_vertically(text, cursor, layout, goal_x, false),
+        CursorMove::Down => move_vertically(text, cursor, layout, goal_x, true),
+        CursorMove::LineStart => line_start(text, cursor),
+        CursorMove::LineEnd => line_end(text, cursor),
+        CursorMove::BufferStart => 0,
+        CursorMove::BufferEnd => text.len(),
+    }
+}
+
+// Skips the whitespace before the cursor, then the word itself.
+fn word_left(text: &str, cursor: usize) -> usize {
+    let before = &text[..cursor];
+    let word_end = before.trim_end().len();
+    before[..word_end]
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_whitespace())
+        .map_or(0, |(i, c)| i + c.len_utf8())
+}
+
+// Skips the rest of the current word, then the whitespace after it.
+fn word_right(text: &str, cursor: usize) -> usize {
+    let after = &text[cursor..];
+    let word_len = after.find(char::is_whitespace).unwrap_or(after.len());
+    let rest = &after[word_len..];
+    cursor + word_len + (rest.len() - rest.trim_start().len())
+}
+
+fn line_start(text: &str, cursor: usize) -> usize {
+    text[..cursor].rfind('\n').map_or(0, |i| i + 1)
+}
+
+fn line_end(text: &str, cursor: usize) -> usize {
+    text[cursor..].find('\n').map_or(text.len(), |i| cursor + i)
+}
+
+fn move_vertically(
+    text: &str,
+    cursor: usize,
+    layout: &TextLayout,
+    goal_x: &mut Option<u16>,
+    down: bool,
+) -> usize {
+    let (x, _) = layout.cursor_position(text, cursor);
+    let goal = *goal_x.get_or_insert(x);
+    let row = layout.line_index(cursor);
+    match (down, row) {
+        (false, 0) => 0,
+        (false, row) => layout.offset_at(text, row - 1, goal),
+        (true, row) if row + 1 >= layout.lines.len() => text.len(),
+        (true, row) => layout.offset_at(text, row + 1, goal),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn punctuation_belongs_to_the_word_it_is_attached_to() {
+        let text = "Hello, world! (again)";
+        assert_eq!(word_right(text, 0), 7);
+        assert_eq!(word_right(text, 7), 14);
+        assert_eq!(word_right(text, 14), text.len());
+        assert_eq!(word_left(text, text.len()), 14);
+        assert_eq!(word_left(text, 14), 7);
+        assert_eq!(word_left(text, 3), 0);
+    }
+
+    #[test]
+    fn runs_of_spaces_are_skipped_as_one() {
+        let text = "one   two    ";
+        assert_eq!(word_right(text, 0), 6);
+        assert_eq!(word_right(text, 4), 6);
+        assert_eq!(word_right(text, 6), text.len());
+        assert_eq!(word_left(text, text.len()), 6);
+        assert_eq!(word_left(text, 5), 0);
+    }
+
+    #[test]
+    fn words_cross_line_boundaries() {
+        let text = "end.\n\n  next\nline";
+        assert_eq!(word_right(text, 0), 8);
+        assert_eq!(word_right(text, 8), 13);
+        assert_eq!(word_left(text, 13), 8);
+        assert_eq!(word_left(text, 8), 0);
+        assert_eq!(word_left(text, 5), 0);
+    }
+
+    #[test]
+    fn multi_byte_graphemes_at_either_end_stay_whole() {
+        let family = "👨\u{200d}👩\u{200d}👧";
+        let text = format!("{} e\u{301}te\u{301} {}", family, family);
+        let text = text.as_str();
+        let second_word = family.len() + 1;
+        let last_word = text.len() - family.len();
+
+        assert_eq!(word_right(text, 0), second_word);
+        assert_eq!(word_right(text, second_word), last_word);
+        assert_eq!(word_right(text, last_word), text.len());
+        assert_eq!(word_left(text, text.len()), last_word);
+        assert_eq!(word_left(text, last_word), second_word);
+        assert_eq!(word_left(text, second_word), 0);
+        assert_eq!(word_left(text, 0), 0);
+        assert_eq!(word_right(text, text.len()), text.len());
+    }
+}
@@ -1,124 +1,245 @@
 use crossterm::event::{KeyCode, KeyEventKind, KeyModifiers};
 use std::io;
 
-use crate::app_logic::{App, AppState, PanelFocus};
-use crate::config::DEFAULT_SESSION_DURATION_MINS;
+use crate::app_logic::{App, AppState, MenuItem, PanelFocus, SessionAction, WordGoal};
+use crate::config::{Config, DEFAULT_WORD_GOAL};
+use crate::editor::CursorMove;
+use crate::sprint::SprintSeries;
 
 pub fn handle_event(
     app: &mut App,
+    config: &Config,
     key_event_kind: KeyEventKind,
     key_code: KeyCode,
     key_modifiers: KeyModifiers,
 ) -> io::Result<()> {
     if key_event_kind == KeyEventKind::Press {
+        if is_suspend_key(&app.current_state, key_code, key_modifiers) {
+            app.suspend_requested = true;
+            return Ok(());
+        }
         match app.current_state {
-            AppState::MainMenu => handle_main_menu_event(app, key_code)?,
+            AppState::MainMenu => handle_main_menu_event(app, config, key_code)?,
             AppState::Freewrite => handle_freewrite_event(app, key_code, key_modifiers)?,
-            AppState::SessionEnd { .. } => handle_session_end_event(app, key_code),
-            AppState::CustomDurationInput => handle_custom_duration_input_event(app, key_code)?,
+            AppState::SessionEnd { .. } | AppState::SprintSeriesEnd { .. } => {
+                handle_session_end_event(app, key_code)
+            }
+            AppState::SprintBreak => handle_sprint_break_event(app, key_code, key_modifiers),
+            AppState::SessionViewer => handle_session_viewer_event(app, key_code, key_modifiers),
+            AppState::SessionBrowser => handle_session_browser_event(app, key_code, key_modifiers),
+            AppState::Search => handle_search_event(app, key_code, key_modifiers),
+            AppState::RecentFilter => handle_recent_filter_event(app, key_code, key_modifiers),
+            AppState::OpenSession { ref name } => {
+                let name = name.clone();
+                handle_open_session_event(app, key_code, name)
+            }
+            AppState::SessionAction { action, .. } => {
+                handle_session_action_event(app, key_code, action)
+            }
+            AppState::CustomDurationInput => {
+                handle_custom_duration_input_event(app, config, key_code)?
+            }
+            AppState::CustomWordGoalInput => handle_custom_word_goal_input_event(app, key_code),
+            AppState::Notice { .. } => handle_notice_event(app, key_code),
+            AppState::MigrationPrompt => handle_migration_prompt_event(app, key_code),
+            AppState::DraftRecovery => handle_draft_recovery_event(app, key_code),
+            AppState::QuitConfirm => handle_quit_confirm_event(app, key_code),
+            AppState::SaveError => handle_save_error_event(app, key_code),
+            AppState::AlternatePathInput => handle_alternate_path_input_event(app, key_code),
             AppState::Quitting => {}
         }
     }
     Ok(())
 }
 
-fn handle_main_menu_event(app: &mut App, key_code: KeyCode) -> io::Result<()> {
+// In raw mode the terminal doesn't turn Ctrl+Z into SIGTSTP, so suspending is
+// a key binding like any other. The writing area keeps Ctrl+Z for undo and
+// suspends on Alt+Z instead.
+fn is_suspend_key(state: &AppState, key_code: KeyCode, key_modifiers: KeyModifiers) -> bool {
+    match state {
+        AppState::Freewrite => key_code == KeyCode::Char('z') && key_modifiers == KeyModifiers::ALT,
+        _ => key_code == KeyCode::Char('z') && key_modifiers == KeyModifiers::CONTROL,
+    }
+}
+
+fn handle_main_menu_event(app: &mut App, config: &Config, key_code: KeyCode) -> io::Result<()> {
     match key_code {
         KeyCode::Char('q') | KeyCode::Char('Q') => {
             app.current_state = AppState::Quitting;
         }
+        KeyCode::Char('r') => app.reload_sessions(),
+        KeyCode::Char('b') => app.open_browser(),
+        KeyCode::Char('f') => app.open_search(),
+        KeyCode::Char('/') => app.open_recent_filter(),
+        KeyCode::Esc if app.recent_filter.is_some() => app.clear_recent_filter(),
         KeyCode::Char('h') => {
             app.panel_focus = PanelFocus::Sessions;
             app.displayed_file_content = None;
         }
         KeyCode::Char('l') => {
             app.panel_focus = PanelFocus::Recent;
-            if !app.recent_files.is_empty() && app.recent_files[0] != "There's no recent session." {
-                let filename_to_load = app.recent_files[app.selected_recent_index].clone();
-                app.load_file_content_from_name(filename_to_load);
-            } else {
-                app.displayed_file_content = None;
-            }
+            app.load_recent_preview();
         }
         KeyCode::Char('j') => match app.panel_focus {
             PanelFocus::Sessions => {
                 app.selected_menu_index = (app.selected_menu_index + 1) % app.menu_items.len();
             }
-            PanelFocus::Recent => {
-                if !app.recent_files.is_empty()
-                    && app.recent_files[0] != "There's no recent session."
-                {
-                    app.selected_recent_index =
-                        (app.selected_recent_index + 1) % app.recent_files.len();
-                    let filename_to_load = app.recent_files[app.selected_recent_index].clone();
-                    app.load_file_content_from_name(filename_to_load);
-                }
-            }
+            PanelFocus::Recent => app.select_recent_by(1),
         },
         KeyCode::Char('k') => match app.panel_focus {
             PanelFocus::Sessions => {
                 app.selected_menu_index =
                     (app.selected_menu_index + app.menu_items.len() - 1) % app.menu_items.len();
             }
-            PanelFocus::Recent => {
-                if !app.recent_files.is_empty()
-                    && app.recent_files[0] != "There's no recent session."
-                {
-                    app.selected_recent_index =
-                        (app.selected_recent_index + app.recent_files.len() - 1)
-                            % app.recent_files.len();
-                    let filename_to_load = app.recent_files[app.selected_recent_index].clone();
-                    app.load_file_content_from_name(filename_to_load);
-                }
-            }
+            PanelFocus::Recent => app.select_recent_by(-1),
         },
         KeyCode::Enter => match app.panel_focus {
             PanelFocus::Sessions => {
                 let selected_option = &app.menu_items[app.selected_menu_index];
                 let duration = selected_option.to_duration();
 
-                if duration == 0 {
-                    app.custom_duration_input_text = String::new();
-                    app.current_state = AppState::CustomDurationInput;
-                } else {
-                    app.start_session(duration);
+                match selected_option.to_word_goal() {
+                    Some(WordGoal::Ask) => {
+                        app.custom_word_goal_input_text = String::new();
+                        app.current_state = AppState::CustomWordGoalInput;
+                    }
+                    Some(WordGoal::Fixed(goal)) => app.start_word_goal_session(goal),
+                    None if matches!(selected_option, MenuItem::SprintSeries) => {
+                        app.start_sprint_series(SprintSeries::new(config));
+                    }
+                    None if matches!(selected_option, MenuItem::Custom) => {
+                        app.custom_duration_input_text = String::new();
+                        app.current_state = AppState::CustomDurationInput;
+                    }
+                    // 0 for the open-ended session, which counts up
+                    None => app.start_session(duration),
                 }
             }
-            PanelFocus::Recent => {
-                // Action for selecting a recent file, content is already displayed
-            }
+            PanelFocus::Recent => app.open_recent_selection(),
         },
         _ => {}
     }
     Ok(())
 }
 
+// Typing the Recent panel's filter. The list narrows with every key, and the
+// arrows move through it without leaving the filter.
+fn handle_recent_filter_event(app: &mut App, key_code: KeyCode, key_modifiers: KeyModifiers) {
+    let Some(filter) = &mut app.recent_filter else {
+        app.current_state = AppState::MainMenu;
+        return;
+    };
+    match key_code {
+        KeyCode::Char('c') if key_modifiers.contains(KeyModifiers::CONTROL) => {
+            app.current_state = AppState::Quitting;
+        }
+        KeyCode::Enter => app.submit_recent_filter(),
+        KeyCode::Esc => app.clear_recent_filter(),
+        KeyCode::Down => app.select_recent_by(1),
+        KeyCode::Up => app.select_recent_by(-1),
+        KeyCode::Backspace => {
+            filter.pop();
+            app.update_recent_filter();
+        }
+        KeyCode::Char(c) if !c.is_control() => {
+            filter.push(c);
+            app.update_recent_filter();
+        }
+        _ => {}
+    }
+}
+
 fn handle_freewrite_event(
     app: &mut App,
     key_code: KeyCode,
     key_modifiers: KeyModifiers,
 ) -> io::Result<()> {
+    let is_control =
+        |c| key_code == KeyCode::Char(c) && key_modifiers.contains(KeyModifiers::CONTROL);
+    // While paused, getting back to writing resumes the timer
+    if app.clock.is_paused() && key_code != KeyCode::Esc && !is_control('p') && !is_control('c') {
+        app.clock.resume();
+    }
     match key_code {
+        KeyCode::Char('p') if key_modifiers.contains(KeyModifiers::CONTROL) => {
+            app.toggle_pause();
+        }
         KeyCode::Char('c') if key_modifiers.contains(KeyModifiers::CONTROL) => {
-            app.current_state = AppState::Quitting;
+            if app.text.is_empty() {
+                app.current_state = AppState::Quitting;
+            } else {
+                app.current_state = AppState::QuitConfirm;
+            }
+        }
+        // Ctrl+Shift+Z arrives as either an uppercase `Z` or a `z` with SHIFT
+        KeyCode::Char('Z') if key_modifiers.contains(KeyModifiers::CONTROL) => {
+            app.redo();
+        }
+        KeyCode::Char('z')
+            if key_modifiers.contains(KeyModifiers::CONTROL | KeyModifiers::SHIFT) =>
+        {
+            app.redo();
+        }
+        KeyCode::Char('z') if key_modifiers.contains(KeyModifiers::CONTROL) => {
+            app.undo();
+        }
+        KeyCode::Char('y') if key_modifiers.contains(KeyModifiers::CONTROL) => {
+            app.redo();
         }
         KeyCode::Esc => {
             // Save and transition to SessionEnd when escaping
-            app.end_session(); // This correctly sets state to AppState::SessionEnd
-            // app.reset_to_main_menu(); // <--- REMOVE THIS LINE!
+            if let Err(e) = app.end_session() {
+                app.show_save_error(e);
+            }
         }
         KeyCode::Backspace => {
-            if !app.text.is_empty() {
-                app.text.pop();
-            }
+            app.delete_backward();
+        }
+        KeyCode::Delete => {
+            app.delete_forward();
         }
         KeyCode::Enter => {
-            app.text.push('\n');
+            app.insert_char('\n');
         }
-        KeyCode::Char(c) => {
-            if !c.is_control() {
-                app.text.push(c);
-            }
+        KeyCode::Left if key_modifiers.contains(KeyModifiers::CONTROL) => {
+            app.move_cursor(CursorMove::WordLeft);
+        }
+        KeyCode::Right if key_modifiers.contains(KeyModifiers::CONTROL) => {
+            app.move_cursor(CursorMove::WordRight);
+        }
+        KeyCode::Left => {
+            app.move_cursor(CursorMove::Left);
+        }
+        KeyCode::Right => {
+            app.move_cursor(CursorMove::Right);
+        }
+        KeyCode::Up => {
+            app.move_cursor(CursorMove::Up);
+        }
+        KeyCode::Down => {
+            app.move_cursor(CursorMove::Down);
+        }
+        KeyCode::Home if key_modifiers.contains(KeyModifiers::CONTROL) => {
+            app.move_cursor(CursorMove::BufferStart);
+        }
+        KeyCode::End if key_modifiers.contains(KeyModifiers::CONTROL) => {
+            app.move_cursor(CursorMove::BufferEnd);
+        }
+        KeyCode::Home => {
+            app.move_cursor(CursorMove::LineStart);
+        }
+        KeyCode::End => {
+            app.move_cursor(CursorMove::LineEnd);
+        }
+        // Alt+b / Alt+f, the readline word jumps, for terminals that swallow Ctrl+arrows
+        KeyCode::Char('b') if key_modifiers.contains(KeyModifiers::ALT) => {
+            app.move_cursor(CursorMove::WordLeft);
+        }
+        KeyCode::Char('f') if key_modifiers.contains(KeyModifiers::ALT) => {
+            app.move_cursor(CursorMove::WordRight);
+        }
+        KeyCode::Char(c) if !c.is_control() => {
+            app.insert_char(c);
         }
         _ => {}
     }
@@ -138,14 +259,180 @@ fn handle_session_end_event(app: &mut App, key_code: KeyCode) {
     }
 }
 
-fn handle_custom_duration_input_event(app: &mut App, key_code: KeyCode) -> io::Result<()> {
+fn handle_sprint_break_event(app: &mut App, key_code: KeyCode, key_modifiers: KeyModifiers) {
+    match key_code {
+        KeyCode::Enter => app.start_next_sprint(),
+        KeyCode::Esc => app.end_sprint_series(),
+        KeyCode::Char('q') | KeyCode::Char('Q') => {
+            app.current_state = AppState::Quitting;
+        }
+        KeyCode::Char('c') if key_modifiers.contains(KeyModifiers::CONTROL) => {
+            app.current_state = AppState::Quitting;
+        }
+        _ => {}
+    }
+}
+
+fn handle_session_viewer_event(app: &mut App, key_code: KeyCode, key_modifiers: KeyModifiers) {
+    let Some(viewer) = &mut app.viewer else {
+        app.close_viewer();
+        return;
+    };
+    if key_code == KeyCode::Char('c') && key_modifiers.contains(KeyModifiers::CONTROL) {
+        app.current_state = AppState::Quitting;
+        return;
+    }
+    if let Some(input) = &mut viewer.search_input {
+        match key_code {
+            KeyCode::Enter => viewer.submit_search(),
+            KeyCode::Esc => viewer.search_input = None,
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            KeyCode::Char(c) if !c.is_control() => input.push(c),
+            _ => {}
+        }
+        return;
+    }
+    match key_code {
+        KeyCode::Char('j') | KeyCode::Down => viewer.scroll_by(1),
+        KeyCode::Char('k') | KeyCode::Up => viewer.scroll_by(-1),
+        KeyCode::Char('d') if key_modifiers.contains(KeyModifiers::CONTROL) => {
+            viewer.scroll_by(viewer.page_height() as isize / 2)
+        }
+        KeyCode::Char('u') if key_modifiers.contains(KeyModifiers::CONTROL) => {
+            viewer.scroll_by(-(viewer.page_height() as isize / 2))
+        }
+        KeyCode::PageDown | KeyCode::Char(' ') => viewer.page_down(),
+        KeyCode::PageUp | KeyCode::Char('b') => viewer.page_up(),
+        KeyCode::Home | KeyCode::Char('g') => viewer.scroll_to_top(),
+        KeyCode::End | KeyCode::Char('G') => viewer.scroll_to_bottom(),
+        KeyCode::Char('/') => viewer.search_input = Some(String::new()),
+        KeyCode::Char('n') => viewer.next_match(),
+        KeyCode::Char('N') => viewer.previous_match(),
+        KeyCode::Esc if !viewer.matches.is_empty() => viewer.clear_search(),
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => app.close_viewer(),
+        KeyCode::Char('c') => {
+            let name = viewer.name.clone();
+            app.continue_session(&name);
+        }
+        _ => {}
+    }
+}
+
+// Enter on a session of the Recent panel: view it, or write in it again.
+fn handle_open_session_event(app: &mut App, key_code: KeyCode, name: String) {
+    match key_code {
+        KeyCode::Enter | KeyCode::Char('v') | KeyCode::Char('V') => app.view_session(name),
+        KeyCode::Char('c') | KeyCode::Char('C') => app.continue_session(&name),
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => {
+            app.current_state = AppState::MainMenu;
+        }
+        _ => {}
+    }
+}
+
+fn handle_session_browser_event(app: &mut App, key_code: KeyCode, key_modifiers: KeyModifiers) {
+    let Some(browser) = &mut app.browser else {
+        app.close_browser();
+        return;
+    };
+    match key_code {
+        KeyCode::Char('j') | KeyCode::Down => browser.select_by(1),
+        KeyCode::Char('k') | KeyCode::Up => browser.select_by(-1),
+        KeyCode::PageDown => browser.page_down(),
+        KeyCode::PageUp => browser.page_up(),
+        KeyCode::Home => browser.select_first(),
+        KeyCode::End => browser.select_last(),
+        KeyCode::Char('g') => browser.cycle_grouping(),
+        KeyCode::Char('s') => browser.cycle_sort(),
+        KeyCode::Char('r') => browser.reverse(),
+        KeyCode::Enter => app.view_browser_selection(),
+        KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('Q') => app.close_browser(),
+        KeyCode::Char('c') if key_modifiers.contains(KeyModifiers::CONTROL) => {
+            app.current_state = AppState::Quitting;
+        }
+        KeyCode::Tab => app.toggle_browser_trash(),
+        // Each action only applies to saved sessions or to the trash, the
+        // other keys are ignored
+        KeyCode::Char('n') => app.open_session_action(SessionAction::Rename),
+        KeyCode::Char('t') => app.open_session_action(SessionAction::Retitle),
+        KeyCode::Char('c') => app.open_session_action(SessionAction::Duplicate),
+        KeyCode::Char('u') => app.open_session_action(SessionAction::Restore),
+        KeyCode::Char('d') | KeyCode::Delete => {
+            let action = if browser.trash {
+                SessionAction::Purge
+            } else {
+                SessionAction::Trash
+            };
+            app.open_session_action(action);
+        }
+        _ => {}
+    }
+    app.load_browser_preview();
+}
+
+// Confirming an action on a session from the browser, or typing the name or
+// title it asks for.
+fn handle_session_action_event(app: &mut App, key_code: KeyCode, action: SessionAction) {
+    match key_code {
+        KeyCode::Esc => app.cancel_session_action(),
+        KeyCode::Enter => app.confirm_session_action(),
+        KeyCode::Backspace if action.takes_input() => {
+            app.session_action_input.pop();
+        }
+        KeyCode::Char(c) if action.takes_input() && !c.is_control() => {
+            app.session_action_input.push(c);
+        }
+        KeyCode::Char('y') | KeyCode::Char('Y') => app.confirm_session_action(),
+        KeyCode::Char('n') | KeyCode::Char('N') => app.cancel_session_action(),
+        _ => {}
+    }
+}
+
+fn handle_search_event(app: &mut App, key_code: KeyCode, key_modifiers: KeyModifiers) {
+    let Some(search) = &mut app.search else {
+        app.close_search();
+        return;
+    };
+    match key_code {
+        KeyCode::Char('c') if key_modifiers.contains(KeyModifiers::CONTROL) => {
+            app.current_state = AppState::Quitting;
+        }
+        KeyCode::Down => search.select_by(1),
+        KeyCode::Up => search.select_by(-1),
+        KeyCode::PageDown => search.select_by(10),
+        KeyCode::PageUp => search.select_by(-10),
+        KeyCode::Enter => app.view_search_selection(),
+        KeyCode::Esc => app.close_search(),
+        KeyCode::Tab => {
+            search.regex = !search.regex;
+            app.update_search();
+        }
+        KeyCode::Backspace => {
+            search.query.pop();
+            app.update_search();
+        }
+        KeyCode::Char(c) if !c.is_control() => {
+            search.query.push(c);
+            app.update_search();
+        }
+        _ => {}
+    }
+}
+
+fn handle_custom_duration_input_event(
+    app: &mut App,
+    config: &Config,
+    key_code: KeyCode,
+) -> io::Result<()> {
     match key_code {
         KeyCode::Enter => {
             let custom_duration: u64 = app
                 .custom_duration_input_text
                 .trim()
                 .parse()
-                .unwrap_or(DEFAULT_SESSION_DURATION_MINS);
+                .unwrap_or(config.default_session_duration_mins);
 
             app.start_session(custom_duration);
             app.custom_duration_input_text.clear();
@@ -157,12 +444,143 @@ fn handle_custom_duration_input_event(app: &mut App, key_code: KeyCode) -> io::R
         KeyCode::Backspace => {
             app.custom_duration_input_text.pop();
         }
-        KeyCode::Char(c) => {
-            if c.is_ascii_digit() {
-                app.custom_duration_input_text.push(c);
-            }
+        KeyCode::Char(c) if c.is_ascii_digit() => {
+            app.custom_duration_input_text.push(c);
         }
         _ => {}
     }
     Ok(())
 }
+
+fn handle_custom_word_goal_input_event(app: &mut App, key_code: KeyCode) {
+    match key_code {
+        KeyCode::Enter => {
+            let word_goal = app
+                .custom_word_goal_input_text
+                .trim()
+                .parse()
+                .ok()
+                .filter(|goal| *goal > 0)
+                .unwrap_or(DEFAULT_WORD_GOAL);
+
+            app.start_word_goal_session(word_goal);
+            app.custom_word_goal_input_text.clear();
+        }
+        KeyCode::Esc => {
+            app.current_state = AppState::MainMenu;
+            app.custom_word_goal_input_text.clear();
+        }
+        KeyCode::Backspace => {
+            app.custom_word_goal_input_text.pop();
+        }
+        KeyCode::Char(c) if c.is_ascii_digit() => {
+            app.custom_word_goal_input_text.push(c);
+        }
+        _ => {}
+    }
+}
+
+fn handle_notice_event(app: &mut App, key_code: KeyCode) {
+    match key_code {
+        KeyCode::Enter | KeyCode::Esc => {
+            app.dismiss_notice();
+        }
+        KeyCode::Char('q') | KeyCode::Char('Q') => {
+            app.current_state = AppState::Quitting;
+        }
+        _ => {}
+    }
+}
+
+fn handle_migration_prompt_event(app: &mut App, key_code: KeyCode) {
+    match key_code {
+        // Moving the files takes an explicit `y`, Enter keeps them where they are
+        KeyCode::Char('y') | KeyCode::Char('Y') => {
+            app.migrate_legacy_sessions();
+        }
+        KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Enter | KeyCode::Esc => {
+            app.decline_legacy_migration();
+        }
+        _ => {}
+    }
+}
+
+fn handle_draft_recovery_event(app: &mut App, key_code: KeyCode) {
+    let draft_count = app.orphaned_drafts.len();
+    match key_code {
+        KeyCode::Char('j') | KeyCode::Down if draft_count > 0 => {
+            app.selected_draft_index = (app.selected_draft_index + 1) % draft_count;
+        }
+        KeyCode::Char('k') | KeyCode::Up if draft_count > 0 => {
+            app.selected_draft_index = (app.selected_draft_index + draft_count - 1) % draft_count;
+        }
+        KeyCode::Char('r') | KeyCode::Enter => {
+            app.restore_draft();
+        }
+        KeyCode::Char('s') => {
+            app.save_draft();
+        }
+        KeyCode::Char('d') => {
+            app.discard_draft();
+        }
+        KeyCode::Esc => {
+            // Leave the remaining drafts on disk, they'll be offered again next launch
+            app.orphaned_drafts.clear();
+            app.current_state = AppState::MainMenu;
+        }
+        _ => {}
+    }
+}
+
+fn handle_quit_confirm_event(app: &mut App, key_code: KeyCode) {
+    match key_code {
+        KeyCode::Char('s') | KeyCode::Char('S') | KeyCode::Enter => {
+            app.save_and_quit();
+        }
+        KeyCode::Char('d') | KeyCode::Char('D') => {
+            app.discard_and_quit();
+        }
+        KeyCode::Char('k') | KeyCode::Char('K') | KeyCode::Esc => {
+            app.current_state = AppState::Freewrite;
+        }
+        _ => {}
+    }
+}
+
+fn handle_save_error_event(app: &mut App, key_code: KeyCode) {
+    match key_code {
+        KeyCode::Char('r') | KeyCode::Char('R') | KeyCode::Enter => {
+            app.retry_save();
+        }
+        KeyCode::Char('a') | KeyCode::Char('A') => {
+            app.alternate_path_input_text.clear();
+            app.current_state = AppState::AlternatePathInput;
+        }
+        KeyCode::Char('c') | KeyCode::Char('C') => {
+            app.copy_text_to_clipboard();
+        }
+        KeyCode::Esc => {
+            // Keep writing, the draft is still being autosaved
+            app.current_state = AppState::Freewrite;
+        }
+        _ => {}
+    }
+}
+
+fn handle_alternate_path_input_event(app: &mut App, key_code: KeyCode) {
+    match key_code {
+        KeyCode::Enter => {
+            app.save_to_alternate_path();
+        }
+        KeyCode::Esc => {
+            app.current_state = AppState::SaveError;
+        }
+        KeyCode::Backspace => {
+            app.alternate_path_input_text.pop();
+        }
+        KeyCode::Char(c) if !c.is_control() => {
+            app.alternate_path_input_text.push(c);
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,40 @@
+use fuzzy_matcher::{FuzzyMatcher, skim::SkimMatcherV2};
+use std::cmp::Reverse;
+
+use crate::metadata::SessionEntry;
+
+// Fuzzy filter of the Recent panel. A session matches when the query's
+// characters appear in order (not necessarily next to each other) in its file
+// name, title, one of its tags or its first line, so `jnl0612` finds
+// `journal-2025-06-12.txt`. Everything it looks at comes from the index, no
+// file is read while typing.
+
+/// The sessions matching `query`, best match first. Sessions matching equally
+/// well keep their order.
+pub fn fuzzy_filter(sessions: Vec<SessionEntry>, query: &str) -> Vec<SessionEntry> {
+    let matcher = SkimMatcherV2::default().ignore_case();
+    let mut scored: Vec<(i64, SessionEntry)> = sessions
+        .into_iter()
+        .filter_map(|entry| Some((score(&matcher, &entry, query)?, entry)))
+        .collect();
+    scored.sort_by_key(|(score, _)| Reverse(*score));
+    scored.into_iter().map(|(_, entry)| entry).collect()
+}
+
+// How well the best of the session's fields matches, if any does.
+fn score(matcher: &SkimMatcherV2, entry: &SessionEntry, query: &str) -> Option<i64> {
+    let metadata = entry.metadata.as_ref();
+    let title = metadata.and_then(|m| m.title.as_deref());
+    let tags = metadata.map(|m| m.tags.as_slice()).unwrap_or_default();
+    [
+        Some(entry.name.as_str()),
+        title,
+        Some(entry.first_line.as_str()),
+    ]
+    .into_iter()
+    .flatten()
+    .map(str::to_string)
+    .chain(tags.iter().map(|tag| format!("#{}", tag)))
+    .filter_map(|field| matcher.fuzzy_match(&field, query))
+    .max()
+}
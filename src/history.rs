@@ -0,0 +1,335 @@
+use std::{collections::VecDeque, ops::Range};
+
+// Undo / redo for the freewrite buffer. Typing or deleting one character at a
+// time is grouped into word-sized transactions: an edit is merged into the
+// previous one while it continues it in place, and a new transaction starts at
+// each word boundary or after the cursor moves.
+
+// Caps on what the undo stack keeps, so an hour-long session doesn't grow it
+// without bound. The oldest transactions are dropped first.
+const MAX_UNDO_TRANSACTIONS: usize = 1000;
+const MAX_UNDO_BYTES: usize = 1024 * 1024;
+
+/// Replacing `removed` by `inserted` at byte offset `at`.
+#[derive(Clone, Debug)]
+struct Edit {
+    at: usize,
+    removed: String,
+    inserted: String,
+    cursor_before: usize,
+    cursor_after: usize,
+}
+
+impl Edit {
+    fn size(&self) -> usize {
+        self.removed.len() + self.inserted.len()
+    }
+
+    // Folds `next` into this edit if it continues it in place without crossing
+    // a word boundary.
+    fn merge(&mut self, next: &Edit) -> bool {
+        let typing = self.removed.is_empty() && next.removed.is_empty();
+        let backspacing = self.inserted.is_empty() && next.inserted.is_empty();
+        if typing && next.at == self.at + self.inserted.len() {
+            if starts_word(&self.inserted, &next.inserted) {
+                return false;
+            }
+            self.inserted.push_str(&next.inserted);
+        } else if backspacing && next.at + next.removed.len() == self.at {
+            if starts_word(&next.removed, &self.removed) {
+                return false;
+            }
+            self.removed.insert_str(0, &next.removed);
+            self.at = next.at;
+        } else if backspacing && next.at == self.at {
+            if starts_word(&self.removed, &next.removed) {
+                return false;
+            }
+            self.removed.push_str(&next.removed);
+        } else {
+            return false;
+        }
+        self.cursor_after = next.cursor_after;
+        true
+    }
+}
+
+// A word starts where whitespace is followed by something else.
+fn starts_word(before: &str, after: &str) -> bool {
+    let before_is_space = before.chars().next_back().is_some_and(char::is_whitespace);
+    let after_is_space = after.chars().next().is_some_and(char::is_whitespace);
+    before_is_space && !after_is_space
+}
+
+#[derive(Default)]
+pub struct History {
+    undo: VecDeque<Edit>,
+    redo: Vec<Edit>,
+    undo_bytes: usize,
+    // Whether the next edit may be merged into the last undo transaction.
+    open: bool,
+}
+
+impl History {
+    /// Replaces `range` of `text` by `inserted` and records the edit,
+    /// returning where the cursor goes.
+    pub fn replace_range(
+        &mut self,
+        text: &mut String,
+        range: Range<usize>,
+        inserted: &str,
+        cursor: usize,
+    ) -> usize {
+        let removed = text[range.clone()].to_string();
+        text.replace_range(range.clone(), inserted);
+        let cursor_after = range.start + inserted.len();
+        self.record(Edit {
+            at: range.start,
+            removed,
+            inserted: inserted.to_string(),
+            cursor_before: cursor,
+            cursor_after,
+        });
+        cursor_after
+    }
+
+    fn record(&mut self, edit: Edit) {
+        self.redo.clear();
+        if self.open
+            && let Some(last) = self.undo.back_mut()
+        {
+            let last_size = last.size();
+            if last.merge(&edit) {
+                self.undo_bytes = self.undo_bytes - last_size + last.size();
+                self.trim();
+                return;
+            }
+        }
+        self.undo_bytes += edit.size();
+        self.undo.push_back(edit);
+        self.open = true;
+        self.trim();
+    }
+
+    /// Ends the current transaction, e.g. when the cursor is moved.
+    pub fn seal(&mut self) {
+        self.open = false;
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Reverts the last transaction on `text`, returning where the cursor goes.
+    pub fn undo(&mut self, text: &mut String) -> Option<usize> {
+        let edit = self.undo.pop_back()?;
+        self.undo_bytes -= edit.size();
+        self.open = false;
+        text.replace_range(edit.at..edit.at + edit.inserted.len(), &edit.removed);
+        let cursor = edit.cursor_before;
+        self.redo.push(edit);
+        Some(cursor)
+    }
+
+    /// Re-applies the last undone transaction, returning where the cursor goes.
+    pub fn redo(&mut self, text: &mut String) -> Option<usize> {
+        let edit = self.redo.pop()?;
+        text.replace_range(edit.at..edit.at + edit.removed.len(), &edit.inserted);
+        let cursor = edit.cursor_after;
+        self.undo_bytes += edit.size();
+        self.undo.push_back(edit);
+        self.open = false;
+        self.trim();
+        Some(cursor)
+    }
+
+    fn trim(&mut self) {
+        // Always keep the latest transaction, even if it alone is over the limit
+        while self.undo.len() > 1
+            && (self.undo.len() > MAX_UNDO_TRANSACTIONS || self.undo_bytes > MAX_UNDO_BYTES)
+        {
+            if let Some(dropped) = self.undo.pop_front() {
+                self.undo_bytes -= dropped.size();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A text buffer edited through `History` the way the writing area is,
+    // one character at a time.
+    #[derive(Default)]
+    struct Buffer {
+        text: String,
+        cursor: usize,
+        history: History,
+    }
+
+    impl Buffer {
+        fn edit(&mut self, start: usize, end: usize, inserted: &str) {
+            self.cursor =
+                self.history
+                    .replace_range(&mut self.text, start..end, inserted, self.cursor);
+        }
+
+        fn type_text(&mut self, text: &str) {
+            for c in text.chars() {
+                self.edit(self.cursor, self.cursor, &c.to_string());
+            }
+        }
+
+        fn backspace(&mut self, times: usize) {
+            for _ in 0..times {
+                let start = self.text[..self.cursor]
+                    .char_indices()
+                    .next_back()
+                    .map_or(0, |(i, _)| i);
+                self.edit(start, self.cursor, "");
+            }
+        }
+
+        fn delete_forward(&mut self, times: usize) {
+            for _ in 0..times {
+                let end = self.text[self.cursor..]
+                    .chars()
+                    .next()
+                    .map_or(self.cursor, |c| self.cursor + c.len_utf8());
+                self.edit(self.cursor, end, "");
+            }
+        }
+
+        fn move_to(&mut self, cursor: usize) {
+            self.history.seal();
+            self.cursor = cursor;
+        }
+
+        fn undo(&mut self) -> bool {
+            let cursor = self.history.undo(&mut self.text);
+            self.cursor = cursor.unwrap_or(self.cursor);
+            cursor.is_some()
+        }
+
+        fn redo(&mut self) -> bool {
+            let cursor = self.history.redo(&mut self.text);
+            self.cursor = cursor.unwrap_or(self.cursor);
+            cursor.is_some()
+        }
+    }
+
+    #[test]
+    fn typing_is_undone_a_word_at_a_time() {
+        let mut buffer = Buffer::default();
+        buffer.type_text("hello big world");
+
+        assert!(buffer.undo());
+        assert_eq!((buffer.text.as_str(), buffer.cursor), ("hello big ", 10));
+        assert!(buffer.undo());
+        assert_eq!((buffer.text.as_str(), buffer.cursor), ("hello ", 6));
+        assert!(buffer.undo());
+        assert_eq!((buffer.text.as_str(), buffer.cursor), ("", 0));
+        assert!(!buffer.undo());
+    }
+
+    #[test]
+    fn a_new_line_ends_a_word() {
+        let mut buffer = Buffer::default();
+        buffer.type_text("one\n\ntwo");
+
+        assert!(buffer.undo());
+        assert_eq!(buffer.text, "one\n\n");
+        assert!(buffer.undo());
+        assert_eq!(buffer.text, "");
+    }
+
+    #[test]
+    fn backspacing_is_undone_a_word_at_a_time() {
+        let mut buffer = Buffer::default();
+        buffer.type_text("ünï cödé");
+        buffer.backspace(5);
+        assert_eq!(buffer.text, "ünï");
+
+        assert!(buffer.undo());
+        assert_eq!(
+            (buffer.text.as_str(), buffer.cursor),
+            ("ünï ", "ünï ".len())
+        );
+        assert!(buffer.undo());
+        assert_eq!(
+            (buffer.text.as_str(), buffer.cursor),
+            ("ünï cödé", "ünï cödé".len())
+        );
+    }
+
+    #[test]
+    fn deleting_forward_is_one_transaction_per_word() {
+        let mut buffer = Buffer::default();
+        buffer.type_text("abc def");
+        buffer.move_to(0);
+        buffer.delete_forward(2);
+        assert_eq!(buffer.text, "c def");
+
+        assert!(buffer.undo());
+        assert_eq!((buffer.text.as_str(), buffer.cursor), ("abc def", 0));
+    }
+
+    #[test]
+    fn moving_the_cursor_starts_a_new_transaction() {
+        let mut buffer = Buffer::default();
+        buffer.type_text("ab");
+        buffer.move_to(2);
+        buffer.type_text("cd");
+        buffer.move_to(0);
+        buffer.type_text("X");
+        assert_eq!(buffer.text, "Xabcd");
+
+        assert!(buffer.undo());
+        assert_eq!((buffer.text.as_str(), buffer.cursor), ("abcd", 0));
+        assert!(buffer.undo());
+        assert_eq!((buffer.text.as_str(), buffer.cursor), ("ab", 2));
+    }
+
+    #[test]
+    fn redo_reapplies_undone_transactions_in_order() {
+        let mut buffer = Buffer::default();
+        buffer.type_text("one two");
+        buffer.undo();
+        buffer.undo();
+        assert_eq!(buffer.text, "");
+
+        assert!(buffer.redo());
+        assert_eq!((buffer.text.as_str(), buffer.cursor), ("one ", 4));
+        assert!(buffer.redo());
+        assert_eq!((buffer.text.as_str(), buffer.cursor), ("one two", 7));
+        assert!(!buffer.redo());
+    }
+
+    #[test]
+    fn a_new_edit_clears_the_redo_stack() {
+        let mut buffer = Buffer::default();
+        buffer.type_text("one two");
+        buffer.undo();
+        buffer.type_text("three");
+
+        assert!(!buffer.redo());
+        assert_eq!(buffer.text, "one three");
+        assert!(buffer.undo());
+        assert_eq!(buffer.text, "one ");
+    }
+
+    #[test]
+    fn the_oldest_transactions_are_dropped_past_the_limit() {
+        let mut buffer = Buffer::default();
+        for _ in 0..MAX_UNDO_TRANSACTIONS + 5 {
+            buffer.type_text("w ");
+        }
+        let mut undone = 0;
+        while buffer.undo() {
+            undone += 1;
+        }
+        assert_eq!(undone, MAX_UNDO_TRANSACTIONS);
+        assert_eq!(buffer.text, "w ".repeat(5));
+    }
+}
@@ -0,0 +1,482 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::draft;
+use crate::metadata::{self, METADATA_VERSION, SessionEntry, SessionMetadata};
+
+// Index of the saved sessions and their metadata, so the Recent panel and
+// statistics don't have to open every file. It lives in the sessions directory
+// as a JSON lines file that is only ever appended to: each line records a
+// session (or its removal), and a later line for the same file wins.
+//
+// The index is a cache. On startup it is brought in line with the directory,
+// only reading files that are new or were modified since they were indexed,
+// and it can be rebuilt from scratch at any time.
+
+const INDEX_FILE_NAME: &str = ".index.jsonl";
+// The file is compacted on startup once most of its lines are outdated
+const COMPACT_MIN_LINES: usize = 64;
+
+#[derive(Serialize, Deserialize)]
+struct IndexRecord {
+    /// Path relative to the sessions directory, with `/` separators.
+    path: String,
+    #[serde(default)]
+    modified_secs: u64,
+    #[serde(default)]
+    removed: bool,
+    #[serde(default)]
+    metadata: SessionMetadata,
+    /// Missing from lines written by older versions, which `sync` then
+    /// indexes again.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    first_line: Option<String>,
+}
+
+struct IndexedSession {
+    modified_secs: u64,
+    metadata: SessionMetadata,
+    first_line: Option<String>,
+}
+
+pub struct SessionIndex {
+    sessions_dir: PathBuf,
+    sessions: HashMap<String, IndexedSession>,
+    // Lines in the file, to tell when it is worth compacting
+    lines: usize,
+}
+
+impl SessionIndex {
+    /// Loads the index of `sessions_dir` and catches up with any changes made
+    /// to the directory since it was last written.
+    pub fn open(sessions_dir: &Path) -> Self {
+        let mut index = Self {
+            sessions_dir: sessions_dir.to_path_buf(),
+            sessions: HashMap::new(),
+            lines: 0,
+        };
+        if index.load().is_err() {
+            index.sessions.clear();
+        }
+        let _ = index.sync();
+        if index.lines > COMPACT_MIN_LINES.max(2 * index.sessions.len()) {
+            let _ = index.compact();
+        }
+        index
+    }
+
+    /// Forgets everything and indexes the directory again, compacting the file.
+    pub fn rebuild(&mut self) -> io::Result<()> {
+        self.sessions.clear();
+        self.sync()?;
+        self.compact()
+    }
+
+    // Rewrites the file with one line per session.
+    fn compact(&mut self) -> io::Result<()> {
+        let mut lines = String::new();
+        for (name, session) in &self.sessions {
+            lines.push_str(&serde_json::to_string(&IndexRecord {
+                path: name.clone(),
+                modified_secs: session.modified_secs,
+                removed: false,
+                metadata: session.metadata.clone(),
+                first_line: session.first_line.clone(),
+            })?);
+            lines.push('\n');
+        }
+        draft::write_atomic(&self.file_path(), &lines)?;
+        self.lines = self.sessions.len();
+        Ok(())
+    }
+
+    fn file_path(&self) -> PathBuf {
+        self.sessions_dir.join(INDEX_FILE_NAME)
+    }
+
+    fn load(&mut self) -> io::Result<()> {
+        let file = match fs::File::open(self.file_path()) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e),
+        };
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            self.lines += 1;
+            // A line cut short by a crash is skipped, `sync` fills the gap
+            let Ok(record) = serde_json::from_str::<IndexRecord>(&line) else {
+                continue;
+            };
+            self.apply(record);
+        }
+        Ok(())
+    }
+
+    fn apply(&mut self, record: IndexRecord) {
+        if record.removed {
+            self.sessions.remove(&record.path);
+        } else {
+            self.sessions.insert(
+                record.path,
+                IndexedSession {
+                    modified_secs: record.modified_secs,
+                    metadata: record.metadata,
+                    first_line: record.first_line,
+                },
+            );
+        }
+    }
+
+    fn append(&mut self, records: Vec<IndexRecord>) -> io::Result<()> {
+        if records.is_empty() {
+            return Ok(());
+        }
+        let mut lines = String::new();
+        for record in &records {
+            lines.push_str(&serde_json::to_string(record)?);
+            lines.push('\n');
+        }
+        self.lines += records.len();
+        for record in records {
+            self.apply(record);
+        }
+        fs::create_dir_all(&self.sessions_dir)?;
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.file_path())?
+            .write_all(lines.as_bytes())
+    }
+
+    /// Indexes new and modified files and drops the ones that are gone.
+    pub fn sync(&mut self) -> io::Result<()> {
+        let mut files = Vec::new();
+        list_session_files(&self.sessions_dir, &mut files);
+
+        let mut records = Vec::new();
+        let mut present = HashSet::with_capacity(files.len());
+        for (path, modified) in files {
+            let Some(name) = self.relative_name(&path) else {
+                continue;
+            };
+            let modified_secs = secs_since_epoch(modified);
+            if self.sessions.get(&name).is_none_or(|session| {
+                session.modified_secs != modified_secs || session.first_line.is_none()
+            }) {
+                let (metadata, first_line) = read_or_derive_metadata(&path, modified);
+                records.push(IndexRecord {
+                    path: name.clone(),
+                    modified_secs,
+                    removed: false,
+                    metadata,
+                    first_line: Some(first_line),
+                });
+            }
+            present.insert(name);
+        }
+        for name in self.sessions.keys() {
+            if !present.contains(name) {
+                records.push(IndexRecord {
+                    path: name.clone(),
+                    modified_secs: 0,
+                    removed: true,
+                    metadata: SessionMetadata::default(),
+                    first_line: None,
+                });
+            }
+        }
+        self.append(records)
+    }
+
+    /// Records a session that was just saved to `path`, starting with
+    /// `first_line`. Sessions saved outside the sessions directory aren't
+    /// indexed.
+    pub fn record(
+        &mut self,
+        path: &Path,
+        metadata: &SessionMetadata,
+        first_line: &str,
+    ) -> io::Result<()> {
+        let Some(name) = self.relative_name(path) else {
+            return Ok(());
+        };
+        let modified = fs::metadata(path)?.modified()?;
+        self.append(vec![IndexRecord {
+            path: name,
+            modified_secs: secs_since_epoch(modified),
+            removed: false,
+            metadata: metadata.clone(),
+            first_line: Some(first_line.to_string()),
+        }])
+    }
+
+    /// Records that the session at `path` was deleted.
+    pub fn remove(&mut self, path: &Path) -> io::Result<()> {
+        let Some(name) = self.relative_name(path) else {
+            return Ok(());
+        };
+        self.append(vec![IndexRecord {
+            path: name,
+            modified_secs: 0,
+            removed: true,
+            metadata: SessionMetadata::default(),
+            first_line: None,
+        }])
+    }
+
+    /// Records that the session at `from` was moved to `to`.
+    pub fn rename(&mut self, from: &Path, to: &Path) -> io::Result<()> {
+        let session = self
+            .relative_name(from)
+            .and_then(|name| self.sessions.get(&name))
+            .and_then(|session| Some((session.metadata.clone(), session.first_line.clone()?)));
+        self.remove(from)?;
+        match session {
+            Some((metadata, first_line)) => self.record(to, &metadata, &first_line),
+            None => self.add(to),
+        }
+    }
+
+    /// Indexes the session file at `path`, e.g. one moved in from outside the
+    /// index.
+    pub fn add(&mut self, path: &Path) -> io::Result<()> {
+        let Some(name) = self.relative_name(path) else {
+            return Ok(());
+        };
+        let modified = fs::metadata(path)?.modified()?;
+        let (metadata, first_line) = read_or_derive_metadata(path, modified);
+        self.append(vec![IndexRecord {
+            path: name,
+            modified_secs: secs_since_epoch(modified),
+            removed: false,
+            metadata,
+            first_line: Some(first_line),
+        }])
+    }
+
+    /// Every indexed session, newest first.
+    pub fn sessions(&self) -> Vec<SessionEntry> {
+        let mut entries: Vec<SessionEntry> = self
+            .sessions
+            .iter()
+            .map(|(name, session)| SessionEntry {
+                name: name.clone(),
+                metadata: Some(session.metadata.clone()),
+                first_line: session.first_line.clone().unwrap_or_default(),
+            })
+            .collect();
+        metadata::sort_newest_first(&mut entries);
+        entries
+    }
+
+    // Files in hidden directories, like the trash, aren't indexed
+    fn relative_name(&self, path: &Path) -> Option<String> {
+        relative_name(&self.sessions_dir, path)
+            .filter(|name| !name.split('/').any(|part| part.starts_with('.')))
+    }
+}
+
+/// Name of the file at `path` relative to `dir`, with `/` separators.
+pub fn relative_name(dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(dir).ok()?;
+    let parts: Option<Vec<&str>> = relative.iter().map(|part| part.to_str()).collect();
+    Some(parts?.join("/"))
+}
+
+fn secs_since_epoch(time: SystemTime) -> u64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// A session's metadata and first line. Sessions saved without metadata (or by
+/// older versions) still get the basics: their word count, tags, and the
+/// modification time as when they were saved.
+pub fn read_or_derive_metadata(path: &Path, modified: SystemTime) -> (SessionMetadata, String) {
+    match metadata::read_session(path) {
+        Ok((Some(metadata), text)) => (metadata, metadata::first_line(&text)),
+        Ok((None, text)) => (
+            SessionMetadata {
+                version: METADATA_VERSION,
+                ended_at: Some(DateTime::<Local>::from(modified)),
+                word_count: text.split_whitespace().count(),
+                tags: metadata::hashtags(&text),
+                ..SessionMetadata::default()
+            },
+            metadata::first_line(&text),
+        ),
+        Err(_) => (SessionMetadata::default(), String::new()),
+    }
+}
+
+/// Session files below `dir` with when they were last modified. Hidden files
+/// and directories (drafts, the index, ...) and metadata sidecars are skipped.
+pub fn list_session_files(dir: &Path, files: &mut Vec<(PathBuf, SystemTime)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        if entry.file_name().to_string_lossy().starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if file_type.is_dir() {
+            list_session_files(&path, files);
+        } else if file_type.is_file() && !metadata::is_sidecar(&path) {
+            let modified = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .unwrap_or(SystemTime::UNIX_EPOCH);
+            files.push((path, modified));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn write_file(dir: &Path, name: &str, text: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, text).unwrap();
+        path
+    }
+
+    fn names(index: &SessionIndex) -> Vec<String> {
+        let mut names: Vec<String> = index.sessions.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    fn index_lines(dir: &Path) -> Vec<String> {
+        let index = fs::read_to_string(dir.join(INDEX_FILE_NAME)).unwrap();
+        index.lines().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn recorded_renamed_and_removed_sessions_survive_a_reload() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        let a = write_file(dir, "a.txt", "first #idea");
+        let b = write_file(dir, "notes/b.txt", "second");
+        let mut index = SessionIndex::open(dir);
+        assert_eq!(names(&index), ["a.txt", "notes/b.txt"]);
+        assert_eq!(index.sessions["a.txt"].metadata.tags, ["idea"]);
+
+        let metadata = SessionMetadata {
+            word_count: 42,
+            ..SessionMetadata::default()
+        };
+        index.record(&a, &metadata, "as saved").unwrap();
+        let c = dir.join("c.txt");
+        fs::rename(&a, &c).unwrap();
+        index.rename(&a, &c).unwrap();
+        fs::remove_file(&b).unwrap();
+        index.remove(&b).unwrap();
+
+        let index = SessionIndex::open(dir);
+        assert_eq!(names(&index), ["c.txt"]);
+        // Kept from the index rather than read from the file again
+        let entries = index.sessions();
+        assert_eq!(entries[0].metadata.as_ref().unwrap().word_count, 42);
+        assert_eq!(entries[0].first_line, "as saved");
+    }
+
+    #[test]
+    fn a_deleted_index_is_rebuilt_from_the_directory() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        write_file(dir, "a.txt", "one two three");
+        write_file(dir, "2025/b.txt", "four");
+        SessionIndex::open(dir);
+        fs::remove_file(dir.join(INDEX_FILE_NAME)).unwrap();
+
+        let index = SessionIndex::open(dir);
+        assert_eq!(names(&index), ["2025/b.txt", "a.txt"]);
+        assert_eq!(index.sessions["a.txt"].metadata.word_count, 3);
+        assert_eq!(index_lines(dir).len(), 2);
+    }
+
+    #[test]
+    fn a_truncated_last_line_is_skipped() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        write_file(dir, "a.txt", "one");
+        write_file(dir, "b.txt", "two");
+        SessionIndex::open(dir);
+        let lines = index_lines(dir);
+        let last = &lines[1];
+        let cut_short = format!("{}\n{}", lines[0], &last[..last.len() / 2]);
+        fs::write(dir.join(INDEX_FILE_NAME), cut_short).unwrap();
+
+        let index = SessionIndex::open(dir);
+        assert_eq!(names(&index), ["a.txt", "b.txt"]);
+        assert_eq!(index.sessions["b.txt"].first_line.as_deref(), Some("two"));
+    }
+
+    #[test]
+    fn compacting_keeps_only_the_live_sessions() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        let a = write_file(dir, "a.txt", "kept");
+        let b = write_file(dir, "b.txt", "deleted");
+        let mut index = SessionIndex::open(dir);
+        for _ in 0..COMPACT_MIN_LINES {
+            index
+                .record(&a, &SessionMetadata::default(), "kept")
+                .unwrap();
+        }
+        fs::remove_file(&b).unwrap();
+        index.remove(&b).unwrap();
+        assert!(index_lines(dir).len() > COMPACT_MIN_LINES);
+
+        let index = SessionIndex::open(dir);
+        assert_eq!(names(&index), ["a.txt"]);
+        let lines = index_lines(dir);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(index.lines, 1);
+        assert!(lines[0].contains("\"a.txt\""), "{}", lines[0]);
+    }
+
+    #[test]
+    fn sync_picks_up_files_changed_outside_the_app() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        let a = write_file(dir, "a.txt", "before");
+        let b = write_file(dir, "b.txt", "gone soon");
+        let mut index = SessionIndex::open(dir);
+
+        fs::write(&a, "after the edit").unwrap();
+        // Modification times are indexed to the second
+        let later = SystemTime::now() + Duration::from_secs(5);
+        fs::File::options()
+            .write(true)
+            .open(&a)
+            .unwrap()
+            .set_modified(later)
+            .unwrap();
+        fs::remove_file(&b).unwrap();
+        write_file(dir, "new/c.txt", "added");
+        write_file(dir, ".trash/d.txt", "hidden");
+        index.sync().unwrap();
+
+        assert_eq!(names(&index), ["a.txt", "new/c.txt"]);
+        assert_eq!(
+            index.sessions["a.txt"].first_line.as_deref(),
+            Some("after the edit")
+        );
+        assert_eq!(index.sessions["a.txt"].metadata.word_count, 3);
+    }
+}
@@ -0,0 +1,240 @@
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+// Word-wraps the freewrite buffer into screen rows. Rendering draws exactly
+// these rows, and cursor placement / vertical movement read positions from the
+// same layout, so the two can never disagree.
+//
+// Widths are measured per grapheme cluster in terminal cells, so combining
+// marks, emoji sequences and wide (CJK) characters are handled as the terminal
+// draws them.
+
+/// One screen row: a byte range of the text, never including the `\n`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VisualLine {
+    pub start: usize,
+    pub end: usize,
+    /// The row was broken by wrapping, and the same paragraph continues on the
+    /// next row.
+    pub soft_wrapped: bool,
+}
+
+pub struct TextLayout {
+    pub lines: Vec<VisualLine>,
+    pub width: u16,
+}
+
+impl TextLayout {
+    pub fn new(text: &str, width: u16) -> Self {
+        let width = width.max(1);
+        let mut lines = Vec::new();
+        let mut paragraph_start = 0;
+        for paragraph in text.split('\n') {
+            wrap_paragraph(paragraph_start, paragraph, width, &mut lines);
+            paragraph_start += paragraph.len() + 1;
+        }
+        Self { lines, width }
+    }
+
+    /// Index of the row the cursor is drawn on. A cursor sitting exactly at a
+    /// soft wrap belongs to the start of the following row.
+    pub fn line_index(&self, cursor: usize) -> usize {
+        self.lines
+            .iter()
+            .rposition(|line| line.start <= cursor)
+            .unwrap_or(0)
+    }
+
+    /// Screen cell `(x, y)` of the cursor, relative to the top-left of the text.
+    /// A cursor after a row that fills the whole width moves to the next row.
+    pub fn cursor_position(&self, text: &str, cursor: usize) -> (u16, u16) {
+        let row = self.line_index(cursor);
+        let line = &self.lines[row];
+        let x = text[line.start..cursor.min(line.end)].width() as u16;
+        if x >= self.width {
+            (0, row as u16 + 1)
+        } else {
+            (x, row as u16)
+        }
+    }
+
+    /// Number of rows needed to show the text and the cursor.
+    pub fn height(&self, text: &str, cursor: usize) -> u16 {
+        let (_, cursor_y) = self.cursor_position(text, cursor);
+        (self.lines.len() as u16).max(cursor_y + 1)
+    }
+
+    /// Byte offset of the grapheme boundary on `row` closest to, but not past,
+    /// column `x`. On a soft wrapped row the cursor stops before the last
+    /// grapheme, since its end is drawn at the start of the next row.
+    pub fn offset_at(&self, text: &str, row: usize, x: u16) -> usize {
+        let line = &self.lines[row.min(self.lines.len() - 1)];
+        let mut offset = line.start;
+        let mut column = 0;
+        for (i, grapheme) in text[line.start..line.end].grapheme_indices(true) {
+            let next_column = column + grapheme.width() as u16;
+            let is_last = line.start + i + grapheme.len() == line.end;
+            if next_column > x || (is_last && line.soft_wrapped) {
+                return offset;
+            }
+            column = next_column;
+            offset = line.start + i + grapheme.len();
+        }
+        offset
+    }
+}
+
+// Greedy word wrap of a single paragraph (text between two `\n`). Words that
+// don't fit on the current row move to the next one; words wider than a whole
+// row are broken between graphemes.
+fn wrap_paragraph(
+    paragraph_start: usize,
+    paragraph: &str,
+    width: u16,
+    lines: &mut Vec<VisualLine>,
+) {
+    let mut line_start = paragraph_start;
+    let mut column: u16 = 0;
+
+    for (word_offset, word) in paragraph.split_word_bound_indices() {
+        let word_start = paragraph_start + word_offset;
+        let word_width = word.width() as u16;
+        let is_whitespace = word.chars().all(char::is_whitespace);
+
+        if !is_whitespace && column > 0 && column + word_width > width && word_width <= width {
+            lines.push(VisualLine {
+                start: line_start,
+                end: word_start,
+                soft_wrapped: true,
+            });
+            line_start = word_start;
+            column = 0;
+        }
+
+        for (grapheme_offset, grapheme) in word.grapheme_indices(true) {
+            let grapheme_width = grapheme.width() as u16;
+            if column > 0 && column + grapheme_width > width {
+                let break_at = word_start + grapheme_offset;
+                lines.push(VisualLine {
+                    start: line_start,
+                    end: break_at,
+                    soft_wrapped: true,
+                });
+                line_start = break_at;
+                column = 0;
+            }
+            column += grapheme_width;
+        }
+    }
+
+    lines.push(VisualLine {
+        start: line_start,
+        end: paragraph_start + paragraph.len(),
+        soft_wrapped: false,
+    });
+}
+
+/// Start of the grapheme before `cursor`.
+pub fn prev_grapheme(text: &str, cursor: usize) -> usize {
+    text[..cursor]
+        .grapheme_indices(true)
+        .next_back()
+        .map_or(0, |(i, _)| i)
+}
+
+/// End of the grapheme after `cursor`.
+pub fn next_grapheme(text: &str, cursor: usize) -> usize {
+    text[cursor..]
+        .graphemes(true)
+        .next()
+        .map_or(cursor, |g| cursor + g.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows<'a>(text: &'a str, layout: &TextLayout) -> Vec<&'a str> {
+        layout
+            .lines
+            .iter()
+            .map(|line| &text[line.start..line.end])
+            .collect()
+    }
+
+    #[test]
+    fn wraps_by_words() {
+        let text = "the quick brown fox";
+        let layout = TextLayout::new(text, 10);
+        assert_eq!(rows(text, &layout), ["the quick ", "brown fox"]);
+    }
+
+    #[test]
+    fn breaks_words_longer_than_a_row() {
+        let text = "abcdefghij xy";
+        let layout = TextLayout::new(text, 4);
+        assert_eq!(rows(text, &layout), ["abcd", "efgh", "ij ", "xy"]);
+    }
+
+    #[test]
+    fn keeps_hard_line_breaks_and_empty_lines() {
+        let text = "one\n\ntwo";
+        let layout = TextLayout::new(text, 10);
+        assert_eq!(rows(text, &layout), ["one", "", "two"]);
+        assert!(layout.lines.iter().all(|line| !line.soft_wrapped));
+    }
+
+    #[test]
+    fn wide_characters_take_two_cells() {
+        // Each CJK character is two cells wide, so only two fit in 5 cells
+        let text = "日本語です";
+        let layout = TextLayout::new(text, 5);
+        assert_eq!(rows(text, &layout), ["日本", "語で", "す"]);
+        assert_eq!(layout.cursor_position(text, "日本語".len()), (2, 1));
+    }
+
+    #[test]
+    fn combining_marks_and_emoji_are_single_graphemes() {
+        // "e" + COMBINING ACUTE ACCENT, and a family emoji joined with ZWJs
+        let text = "cafe\u{301} 👨‍👩‍👧";
+        let layout = TextLayout::new(text, 20);
+        assert_eq!(layout.cursor_position(text, "cafe\u{301}".len()), (4, 0));
+        assert_eq!(layout.cursor_position(text, text.len()), (7, 0));
+
+        assert_eq!(prev_grapheme(text, "cafe\u{301}".len()), "caf".len());
+        assert_eq!(prev_grapheme(text, text.len()), "cafe\u{301} ".len());
+        assert_eq!(next_grapheme(text, "caf".len()), "cafe\u{301}".len());
+    }
+
+    #[test]
+    fn mixed_scripts_wrap_and_place_the_cursor_consistently() {
+        // Ideographs are words of their own, so a row may break between them
+        let text = "Hello мир 你好世界 done";
+        let layout = TextLayout::new(text, 12);
+        assert_eq!(rows(text, &layout), ["Hello мир 你", "好世界 done"]);
+
+        let cursor = text.find("世").unwrap();
+        assert_eq!(layout.cursor_position(text, cursor), (2, 1));
+        assert_eq!(layout.offset_at(text, 1, 2), cursor);
+        // Column 3 is the middle of a wide character, so the cursor stays before it
+        assert_eq!(layout.offset_at(text, 1, 3), cursor);
+    }
+
+    #[test]
+    fn cursor_at_a_soft_wrap_starts_the_next_row() {
+        let text = "abcd efgh";
+        let layout = TextLayout::new(text, 5);
+        assert_eq!(rows(text, &layout), ["abcd ", "efgh"]);
+        assert_eq!(layout.cursor_position(text, 5), (0, 1));
+        // Moving onto the end of a soft wrapped row stops before its last grapheme
+        assert_eq!(layout.offset_at(text, 0, 10), 4);
+    }
+
+    #[test]
+    fn cursor_after_a_full_row_moves_to_a_new_row() {
+        let text = "abcde";
+        let layout = TextLayout::new(text, 5);
+        assert_eq!(layout.cursor_position(text, 5), (0, 1));
+        assert_eq!(layout.height(text, 5), 2);
+    }
+}
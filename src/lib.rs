@@ -2,8 +2,26 @@
 // These modules are now *part of* the 'freewriter_tui' library crate.
 // They will be accessible as `freewriter_tui::config`, `freewriter_rs::event`, etc.
 pub mod app_logic;
+pub mod browser;
+pub mod clipboard;
+pub mod clock;
 pub mod config;
+pub mod daily;
+pub mod draft;
+pub mod editor;
 pub mod event;
+pub mod filter;
+pub mod history;
+pub mod index;
+pub mod layout;
+pub mod metadata;
+pub mod naming;
+pub mod search;
+pub mod sprint;
+pub mod stats;
+pub mod storage;
 pub mod ui;
+pub mod viewer;
 
-pub use app_logic::{App, AppState, MenuItem, PanelFocus};
+pub use app_logic::{App, AppState, MenuItem, PanelFocus, SessionSummary};
+pub use config::Config;
@@ -1,32 +1,122 @@
 use crossterm::{
     ExecutableCommand,
-    event::{self as crossterm_event, Event}, // Alias event to avoid conflict with our own `event` module
+    event::{self as crossterm_event, DisableFocusChange, EnableFocusChange, Event}, // Alias event to avoid conflict with our own `event` module
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use ratatui::{Terminal, backend::CrosstermBackend};
 use std::{
-    io::{self, stdout},
+    io::{self, Stdout, stdout},
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
     time::Duration,
 };
 
 // Import everything we need directly from your library (src/lib.rs)
+use freewrite_tui::config::{CliArgs, USAGE};
 use freewrite_tui::event;
 use freewrite_tui::ui;
-use freewrite_tui::{App, AppState};
+use freewrite_tui::{App, AppState, Config};
 
 fn main() -> io::Result<()> {
-    enable_raw_mode()?;
-    stdout().execute(EnterAlternateScreen)?;
+    let args = match CliArgs::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            std::process::exit(2);
+        }
+    };
+    if args.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+
+    let (config, config_errors) = Config::load(&args);
+
+    // SIGTERM / SIGHUP only raise a flag, the main loop saves and exits cleanly
+    let shutdown_requested = Arc::new(AtomicBool::new(false));
+    signal_hook::flag::register(
+        signal_hook::consts::SIGTERM,
+        Arc::clone(&shutdown_requested),
+    )?;
+    #[cfg(unix)]
+    signal_hook::flag::register(signal_hook::consts::SIGHUP, Arc::clone(&shutdown_requested))?;
+
+    // Job control: SIGTSTP, like the suspend key, is handled by the main loop
+    // so it can pause the session and leave the terminal usable before
+    // stopping, and SIGCONT tells it the process was stopped in some other way
+    // (e.g. SIGSTOP).
+    let job_control = JobControl::default();
+    #[cfg(unix)]
+    {
+        signal_hook::flag::register(
+            signal_hook::consts::SIGTSTP,
+            Arc::clone(&job_control.suspend_requested),
+        )?;
+        signal_hook::flag::register(
+            signal_hook::consts::SIGCONT,
+            Arc::clone(&job_control.continued),
+        )?;
+    }
+
+    // Restore the terminal before the panic message is printed, otherwise it is
+    // garbled by raw mode and hidden by the alternate screen.
+    let default_panic_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        default_panic_hook(info);
+    }));
+
+    setup_terminal()?;
     let mut terminal = Terminal::new(CrosstermBackend::new(stdout()))?;
 
-    let mut app = App::new();
+    let mut app = App::new(&config);
+    if !config_errors.is_empty() {
+        let mut messages =
+            vec!["Some settings were ignored and defaults used instead:".to_string()];
+        messages.extend(config_errors);
+        app.show_notice("Config Error", messages);
+    }
+
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        run(
+            &mut terminal,
+            &mut app,
+            &config,
+            &shutdown_requested,
+            &job_control,
+        )
+    }));
+
+    restore_terminal()?;
+
+    // Whatever went wrong, never lose the text of an unsaved session
+    if !matches!(result, Ok(Ok(())))
+        && let Some(path) = app.write_rescue_file()
+    {
+        eprintln!("Your unsaved writing was rescued to {}", path.display());
+    }
+    match result {
+        Ok(result) => result,
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
 
+fn run(
+    terminal: &mut Terminal<CrosstermBackend<Stdout>>,
+    app: &mut App,
+    config: &Config,
+    shutdown_requested: &AtomicBool,
+    job_control: &JobControl,
+) -> io::Result<()> {
     let tick_rate = Duration::from_millis(100);
     let last_tick = std::time::Instant::now();
 
     loop {
         // Draw UI
-        terminal.draw(|frame| ui::ui(frame, &app))?;
+        terminal.draw(|frame| ui::ui(frame, app, config))?;
 
         // Handle events
         let timeout = tick_rate
@@ -35,16 +125,36 @@ fn main() -> io::Result<()> {
 
         if crossterm_event::poll(timeout)? {
             // Use aliased crossterm_event
-            if let Event::Key(key_event) = crossterm_event::read()? {
-                event::handle_event(
-                    &mut app,
+            match crossterm_event::read()? {
+                Event::Key(key_event) => event::handle_event(
+                    app,
+                    config,
                     key_event.kind,
                     key_event.code,
                     key_event.modifiers,
-                )?;
+                )?,
+                // Switching to another window pauses the session timer
+                Event::FocusLost => app.pause_session(),
+                _ => {}
             }
         }
 
+        let suspend_key_pressed = std::mem::take(&mut app.suspend_requested);
+        if job_control.suspend_requested.swap(false, Ordering::Relaxed) || suspend_key_pressed {
+            app.pause_session();
+            suspend(terminal)?;
+        }
+        if job_control.continued.swap(false, Ordering::Relaxed) {
+            app.pause_after_suspend();
+            terminal.clear()?;
+        }
+
+        // There's nobody left to confirm with, so save what we have and leave
+        if shutdown_requested.load(Ordering::Relaxed) {
+            app.save_before_exit();
+            break;
+        }
+
         // Update application state
         app.update();
 
@@ -54,9 +164,41 @@ fn main() -> io::Result<()> {
         }
     }
 
-    // Restore terminal
-    stdout().execute(LeaveAlternateScreen)?;
-    disable_raw_mode()?;
+    Ok(())
+}
+
+#[derive(Default)]
+struct JobControl {
+    suspend_requested: Arc<AtomicBool>,
+    continued: Arc<AtomicBool>,
+}
+
+fn setup_terminal() -> io::Result<()> {
+    enable_raw_mode()?;
+    stdout()
+        .execute(EnterAlternateScreen)?
+        .execute(EnableFocusChange)?;
+    Ok(())
+}
+
+fn restore_terminal() -> io::Result<()> {
+    stdout()
+        .execute(DisableFocusChange)?
+        .execute(LeaveAlternateScreen)?;
+    disable_raw_mode()
+}
+
+// Hands the terminal back to the shell and stops the process, like the default
+// SIGTSTP action would. Execution continues here once the job is resumed.
+#[cfg(unix)]
+fn suspend(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> io::Result<()> {
+    restore_terminal()?;
+    signal_hook::low_level::raise(signal_hook::consts::SIGSTOP)?;
+    setup_terminal()?;
+    terminal.clear()
+}
 
+#[cfg(not(unix))]
+fn suspend(_terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> io::Result<()> {
     Ok(())
 }
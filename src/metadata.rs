@@ -0,0 +1,279 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+// Structured data about a saved session, stored next to its text so it
+// outlives the summary screen. It is kept either in a JSON sidecar file
+// (`<session>.json`) or as YAML frontmatter at the top of the session file.
+//
+// The frontmatter is written as one `key: value` per line with the values
+// encoded as JSON, which is also valid YAML, so it stays readable by other
+// tools without pulling in a YAML library.
+
+/// Bumped whenever the meaning of an existing field changes. New fields don't
+/// need a bump: missing fields take their defaults and unknown ones are
+/// ignored, so old and new versions can read each other's files.
+pub const METADATA_VERSION: u32 = 1;
+
+const FRONTMATTER_DELIMITER: &str = "---";
+const SIDECAR_EXTENSION: &str = "json";
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionMetadata {
+    pub version: u32,
+    pub started_at: Option<DateTime<Local>>,
+    pub ended_at: Option<DateTime<Local>>,
+    pub mode: SessionMode,
+    /// Active writing time, pauses excluded.
+    pub duration_secs: u64,
+    pub wall_duration_secs: u64,
+    /// Planned length of timed sessions.
+    pub target_mins: Option<u64>,
+    pub word_goal: Option<usize>,
+    pub word_count: usize,
+    pub wpm: f64,
+    pub prompt: Option<String>,
+    /// Given by the writer, shown next to the file name.
+    pub title: Option<String>,
+    /// The `#hashtags` found in the text, lowercased.
+    pub tags: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum SessionMode {
+    Timed,
+    OpenEnded,
+    WordGoal,
+    Sprint,
+    /// Saved from a recovered draft, so the timing is unknown.
+    Draft,
+    /// A day's sessions appended to one file.
+    DailyPage,
+    /// A mode written by a newer version.
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+/// Where session metadata is written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetadataFormat {
+    Sidecar,
+    Frontmatter,
+    Off,
+}
+
+pub fn sidecar_path(session_path: &Path) -> PathBuf {
+    session_path.with_extension(SIDECAR_EXTENSION)
+}
+
+pub fn is_sidecar(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == SIDECAR_EXTENSION)
+}
+
+/// The text to write to the session file, with the metadata on top when it is
+/// kept as frontmatter.
+pub fn with_frontmatter(metadata: &SessionMetadata, text: &str) -> String {
+    let mut contents = format!("{}\n", FRONTMATTER_DELIMITER);
+    if let Ok(Value::Object(mut fields)) = serde_json::to_value(metadata) {
+        // The version goes first, the rest follows in alphabetical order
+        let version = fields.remove("version").unwrap_or_default();
+        contents.push_str(&format!("version: {}\n", version));
+        for (key, value) in fields {
+            if !value.is_null() {
+                contents.push_str(&format!("{}: {}\n", key, value));
+            }
+        }
+    }
+    contents.push_str(&format!("{}\n\n{}", FRONTMATTER_DELIMITER, text));
+    contents
+}
+
+/// Splits frontmatter written by `with_frontmatter` from the text after it.
+/// Text that merely starts with `---` is returned untouched.
+pub fn split_frontmatter(contents: &str) -> (Option<SessionMetadata>, &str) {
+    let Some(rest) = contents.strip_prefix("---\n") else {
+        return (None, contents);
+    };
+    let Some((header, body)) = rest.split_once("\n---\n") else {
+        return (None, contents);
+    };
+    let mut fields = Map::new();
+    for line in header.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            return (None, contents);
+        };
+        let value = value.trim();
+        let value = serde_json::from_str(value).unwrap_or_else(|_| Value::from(value));
+        fields.insert(key.trim().to_string(), value);
+    }
+    if !fields.contains_key("version") {
+        return (None, contents);
+    }
+    match serde_json::from_value(Value::Object(fields)) {
+        Ok(metadata) => (Some(metadata), body.strip_prefix('\n').unwrap_or(body)),
+        Err(_) => (None, contents),
+    }
+}
+
+pub fn to_json(metadata: &SessionMetadata) -> String {
+    serde_json::to_string_pretty(metadata).unwrap_or_default()
+}
+
+pub fn from_json(contents: &str) -> Option<SessionMetadata> {
+    serde_json::from_str(contents).ok()
+}
+
+/// Reads a session file, returning its metadata (from frontmatter or a
+/// sidecar, whichever there is) and its text without the frontmatter.
+pub fn read_session(path: &Path) -> std::io::Result<(Option<SessionMetadata>, String)> {
+    let contents = fs::read_to_string(path)?;
+    let (metadata, text) = split_frontmatter(&contents);
+    let metadata = metadata.or_else(|| read_sidecar(path));
+    Ok((metadata, text.to_string()))
+}
+
+/// Metadata of a session file, without reading the text when there's a sidecar.
+pub fn read_metadata(path: &Path) -> Option<SessionMetadata> {
+    read_sidecar(path).or_else(|| {
+        let contents = fs::read_to_string(path).ok()?;
+        split_frontmatter(&contents).0
+    })
+}
+
+fn read_sidecar(session_path: &Path) -> Option<SessionMetadata> {
+    from_json(&fs::read_to_string(sidecar_path(session_path)).ok()?)
+}
+
+/// A saved session as listed in the Recent panel.
+#[derive(Clone, Debug)]
+pub struct SessionEntry {
+    /// File name relative to the sessions directory.
+    pub name: String,
+    pub metadata: Option<SessionMetadata>,
+    /// Start of the text, for filtering without reading the file.
+    pub first_line: String,
+}
+
+impl SessionEntry {
+    /// When the session was saved, or started if that's all there is.
+    pub fn saved_at(&self) -> Option<DateTime<Local>> {
+        let metadata = self.metadata.as_ref()?;
+        metadata.ended_at.or(metadata.started_at)
+    }
+}
+
+pub fn sort_newest_first(entries: &mut [SessionEntry]) {
+    entries.sort_by(|a, b| (b.saved_at(), &b.name).cmp(&(a.saved_at(), &a.name)));
+}
+
+/// The `#hashtags` in `text`, lowercased, sorted and without duplicates. A `#`
+/// only starts a tag at the beginning of a word and when a letter or digit
+/// follows, so markdown headings and `##` aren't tags.
+pub fn hashtags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = text
+        .split_whitespace()
+        .filter_map(|word| {
+            let tag: String = word
+                .strip_prefix('#')?
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '-' || *c == '_')
+                .collect();
+            tag.starts_with(char::is_alphanumeric)
+                .then(|| tag.to_lowercase())
+        })
+        .collect();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+// Longest first line kept in the index
+const FIRST_LINE_MAX_CHARS: usize = 120;
+
+/// The first line of `text` that isn't blank, trimmed and cut short.
+pub fn first_line(text: &str) -> String {
+    text.lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+        .chars()
+        .take(FIRST_LINE_MAX_CHARS)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn metadata() -> SessionMetadata {
+        let started_at = Local.with_ymd_and_hms(2025, 6, 12, 7, 30, 0).unwrap();
+        SessionMetadata {
+            version: METADATA_VERSION,
+            started_at: Some(started_at),
+            ended_at: Some(started_at + chrono::Duration::minutes(25)),
+            mode: SessionMode::Timed,
+            duration_secs: 1490,
+            wall_duration_secs: 1500,
+            target_mins: Some(25),
+            word_goal: None,
+            word_count: 612,
+            wpm: 24.6,
+            prompt: Some("What woke you: the light, or \"the noise\"?".to_string()),
+            title: Some("Morning: part one\nand two".to_string()),
+            tags: vec!["dreams".to_string(), "morning-pages".to_string()],
+        }
+    }
+
+    #[test]
+    fn frontmatter_round_trips() {
+        let text = "The morning light was soft.\n\nNothing else.";
+        let contents = with_frontmatter(&metadata(), text);
+        assert!(contents.starts_with("---\nversion: 1\n"));
+
+        let (parsed, body) = split_frontmatter(&contents);
+        assert_eq!(parsed, Some(metadata()));
+        assert_eq!(body, text);
+    }
+
+    #[test]
+    fn frontmatter_round_trips_a_text_starting_like_frontmatter() {
+        let text = "---\ntitle: not metadata\n---\n\nstill the text";
+        let contents = with_frontmatter(&metadata(), text);
+
+        let (parsed, body) = split_frontmatter(&contents);
+        assert_eq!(parsed, Some(metadata()));
+        assert_eq!(body, text);
+    }
+
+    #[test]
+    fn text_that_merely_starts_with_dashes_is_left_untouched() {
+        for text in [
+            "---\n",
+            "---\nno closing delimiter",
+            "---\njust a line\n---\ntext",
+            "---\ntitle: \"no version\"\n---\ntext",
+            "----\nversion: 1\n---\ntext",
+        ] {
+            assert_eq!(split_frontmatter(text), (None, text));
+        }
+    }
+
+    #[test]
+    fn sidecars_round_trip_and_tolerate_other_versions() {
+        assert_eq!(from_json(&to_json(&metadata())), Some(metadata()));
+
+        let newer = r#"{"version": 2, "word_count": 3, "mode": "dictation", "mood": "calm"}"#;
+        let parsed = from_json(newer).unwrap();
+        assert_eq!(parsed.word_count, 3);
+        assert_eq!(parsed.mode, SessionMode::Unknown);
+        assert!(parsed.tags.is_empty());
+    }
+}
@@ -0,0 +1,316 @@
+use chrono::{
+    DateTime, Local,
+    format::{Item, StrftimeItems},
+};
+use std::{
+    fmt::Write,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::metadata;
+
+// Names of saved session files, built from the `filename_template` setting:
+// strftime fields (`%Y`, `%H`, ...) for when the session was saved, plus
+// `{slug}`, `{words}` and `{duration}` for what was written.
+//
+// Names never contain characters that some filesystems reject (like `:`), and
+// an existing session is never overwritten: a `-2`, `-3`, ... suffix is added
+// instead.
+
+pub const DEFAULT_FILENAME_TEMPLATE: &str = "%Y-%m-%d_%H-%M-%S_freewritesession";
+const SESSION_EXTENSION: &str = "txt";
+const SLUG_MAX_WORDS: usize = 6;
+const SLUG_MAX_LEN: usize = 40;
+const UNTITLED_SLUG: &str = "untitled";
+
+/// Optional subdirectories of the sessions directory that sessions are sorted into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Subdirectories {
+    None,
+    /// `2025/`
+    Year,
+    /// `2025/06/`
+    Month,
+}
+
+/// What a session's file name is made of.
+pub struct NameFields<'a> {
+    pub saved_at: DateTime<Local>,
+    pub text: &'a str,
+    /// Active writing time of the session.
+    pub duration: Duration,
+}
+
+/// Checks the strftime fields of a template, so a typo is reported once at
+/// startup rather than failing every save.
+pub fn validate_template(template: &str) -> Result<(), String> {
+    if template.trim().is_empty() {
+        return Err("filename_template must not be empty".to_string());
+    }
+    if StrftimeItems::new(template).any(|item| matches!(item, Item::Error)) {
+        return Err(format!(
+            "invalid strftime field in filename_template `{}`",
+            template
+        ));
+    }
+    Ok(())
+}
+
+/// Name of a new session relative to the sessions directory, with `/`
+/// separators. It may be taken already, see `unique_name`.
+pub fn session_name(template: &str, subdirectories: Subdirectories, fields: &NameFields) -> String {
+    format!(
+        "{}{}.{}",
+        subdirectory(subdirectories, fields.saved_at),
+        file_stem(template, fields),
+        SESSION_EXTENSION
+    )
+}
+
+/// Name of the daily page every session written on `day` is appended to,
+/// e.g. `2025-06-12.txt`, in the same subdirectories as other sessions.
+pub fn daily_page_name(subdirectories: Subdirectories, day: DateTime<Local>) -> String {
+    format!(
+        "{}{}.{}",
+        subdirectory(subdirectories, day),
+        day.format("%Y-%m-%d"),
+        SESSION_EXTENSION
+    )
+}
+
+// The folders a session saved `at` goes in, with a trailing `/`.
+fn subdirectory(subdirectories: Subdirectories, at: DateTime<Local>) -> String {
+    match subdirectories {
+        Subdirectories::None => String::new(),
+        Subdirectories::Year => at.format("%Y/").to_string(),
+        Subdirectories::Month => at.format("%Y/%m/").to_string(),
+    }
+}
+
+/// `name`, or the first of `name-2`, `name-3`, ... that isn't `taken`. The
+/// number goes before the extension.
+pub fn unique_name(name: &str, taken: impl Fn(&str) -> bool) -> String {
+    if !taken(name) {
+        return name.to_string();
+    }
+    let file_start = name.rfind('/').map_or(0, |i| i + 1);
+    let (stem, extension) = match name[file_start..].rfind('.') {
+        Some(dot) if dot > 0 => name.split_at(file_start + dot),
+        _ => (name, ""),
+    };
+    (2..)
+        .map(|n| format!("{}-{}{}", stem, n, extension))
+        .find(|name| !taken(name))
+        .unwrap_or_else(|| name.to_string())
+}
+
+/// A session name typed by the writer, e.g. to rename a session, checked and
+/// given the session extension if it has none.
+pub fn parse_session_name(input: &str) -> Result<String, String> {
+    let name = input.trim();
+    if name.is_empty() {
+        return Err("The name must not be empty".to_string());
+    }
+    let parts: Vec<&str> = name.split('/').collect();
+    if parts.iter().any(|part| part.is_empty()) {
+        return Err("The name must not start or end with `/`".to_string());
+    }
+    // Hidden files are left out of the session list, like drafts
+    if parts.iter().any(|part| part.starts_with('.')) {
+        return Err("Folders and files must not start with `.`".to_string());
+    }
+    if metadata::is_sidecar(Path::new(name)) {
+        return Err("`.json` files are kept for metadata".to_string());
+    }
+    if parts.last().is_some_and(|file| file.contains('.')) {
+        Ok(name.to_string())
+    } else {
+        Ok(format!("{}.{}", name, SESSION_EXTENSION))
+    }
+}
+
+/// Path for a new session file in `sessions_dir`, not taken by any existing one.
+pub fn session_path(
+    sessions_dir: &Path,
+    template: &str,
+    subdirectories: Subdirectories,
+    fields: &NameFields,
+) -> PathBuf {
+    let taken = |name: &str| {
+        let path = sessions_dir.join(name);
+        path.exists() || metadata::sidecar_path(&path).exists()
+    };
+    sessions_dir.join(unique_name(
+        &session_name(template, subdirectories, fields),
+        taken,
+    ))
+}
+
+/// The file name, without extension, `template` gives for `fields`.
+pub fn file_stem(template: &str, fields: &NameFields) -> String {
+    let secs = fields.duration.as_secs();
+    let expanded = template
+        .replace("{slug}", &slug(fields.text))
+        .replace(
+            "{words}",
+            &fields.text.split_whitespace().count().to_string(),
+        )
+        .replace("{duration}", &format!("{}m{:02}s", secs / 60, secs % 60));
+    let mut stem = String::new();
+    if write!(stem, "{}", fields.saved_at.format(&expanded)).is_err() {
+        stem = fields
+            .saved_at
+            .format(DEFAULT_FILENAME_TEMPLATE)
+            .to_string();
+    }
+    sanitize(&stem)
+}
+
+/// The first few words of `text`, lowercased and joined by dashes.
+pub fn slug(text: &str) -> String {
+    let mut slug = String::new();
+    for word in text.split_whitespace().take(SLUG_MAX_WORDS) {
+        let word: String = word
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect();
+        if word.is_empty() {
+            continue;
+        }
+        if slug.len() + word.len() + 1 > SLUG_MAX_LEN && !slug.is_empty() {
+            break;
+        }
+        if !slug.is_empty() {
+            slug.push('-');
+        }
+        slug.push_str(&word);
+    }
+    if slug.is_empty() {
+        UNTITLED_SLUG.to_string()
+    } else {
+        slug.chars().take(SLUG_MAX_LEN).collect()
+    }
+}
+
+// Replaces what Windows, FAT and most sync services reject, and path separators.
+fn sanitize(stem: &str) -> String {
+    let sanitized: String = stem
+        .chars()
+        .map(|c| match c {
+            '<' | '>' | ':' | '"' | '/' | '\\' | '|' | '?' | '*' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect();
+    // Windows also drops trailing dots and spaces
+    let sanitized = sanitized.trim_end_matches(['.', ' ']);
+    if sanitized.is_empty() {
+        UNTITLED_SLUG.to_string()
+    } else {
+        sanitized.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn fields(text: &str) -> NameFields<'_> {
+        NameFields {
+            saved_at: Local.with_ymd_and_hms(2025, 6, 12, 10, 30, 0).unwrap(),
+            text,
+            duration: Duration::from_secs(25 * 60 + 3),
+        }
+    }
+
+    #[test]
+    fn file_stems_follow_the_template() {
+        assert_eq!(
+            file_stem(
+                "%Y-%m-%d_{slug}_{words}w_{duration}",
+                &fields("The morning light was soft")
+            ),
+            "2025-06-12_the-morning-light-was-soft_5w_25m03s"
+        );
+        assert_eq!(
+            file_stem(DEFAULT_FILENAME_TEMPLATE, &fields("")),
+            "2025-06-12_10-30-00_freewritesession"
+        );
+    }
+
+    #[test]
+    fn session_names_follow_the_template() {
+        let name = session_name(
+            "%Y-%m-%d_{slug}_{words}w_{duration}",
+            Subdirectories::Month,
+            &fields("The morning light was soft"),
+        );
+        assert_eq!(
+            name,
+            "2025/06/2025-06-12_the-morning-light-was-soft_5w_25m03s.txt"
+        );
+    }
+
+    #[test]
+    fn characters_filesystems_reject_are_replaced() {
+        assert_eq!(file_stem("%H:%M {slug}?", &fields("a/b")), "10-30 ab-");
+        assert_eq!(file_stem("notes. . ", &fields("")), "notes");
+        assert_eq!(file_stem("...", &fields("")), UNTITLED_SLUG);
+    }
+
+    #[test]
+    fn slugs_keep_non_ascii_letters() {
+        assert_eq!(slug("Ça va? Très bien, merci!"), "ça-va-très-bien-merci");
+        assert_eq!(slug("日本語の テキスト"), "日本語の-テキスト");
+        assert_eq!(slug("  ...  !!! "), UNTITLED_SLUG);
+
+        let long = slug(&"ünïcödé ".repeat(10));
+        assert!(long.chars().count() <= SLUG_MAX_LEN);
+        assert!(long.starts_with("ünïcödé-ünïcödé"));
+        assert!(!long.ends_with('-'));
+    }
+
+    #[test]
+    fn taken_names_get_a_numbered_suffix_before_the_extension() {
+        let taken = ["a.txt", "a-2.txt", "2025/06/b.txt", "dir.v1/notes"];
+        let is_taken = |name: &str| taken.contains(&name);
+
+        assert_eq!(unique_name("free.txt", is_taken), "free.txt");
+        assert_eq!(unique_name("a.txt", is_taken), "a-3.txt");
+        assert_eq!(unique_name("2025/06/b.txt", is_taken), "2025/06/b-2.txt");
+        // A dot in a folder name isn't an extension
+        assert_eq!(unique_name("dir.v1/notes", is_taken), "dir.v1/notes-2");
+    }
+
+    #[test]
+    fn typed_session_names_get_the_session_extension() {
+        assert_eq!(
+            parse_session_name("  journal/morning "),
+            Ok("journal/morning.txt".to_string())
+        );
+        assert_eq!(parse_session_name("notes.md"), Ok("notes.md".to_string()));
+    }
+
+    #[test]
+    fn typed_session_names_cant_leave_the_listed_sessions() {
+        for name in [
+            "",
+            "   ",
+            "/etc/passwd",
+            "journal/",
+            "a//b",
+            "..",
+            "../outside",
+            "journal/../../outside",
+            ".trash/restored",
+            "journal/.hidden",
+            ".drafts/2025-06-12_10-30-00.draft",
+            "session.json",
+        ] {
+            assert!(parse_session_name(name).is_err(), "{:?} was accepted", name);
+        }
+    }
+}
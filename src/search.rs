@@ -0,0 +1,341 @@
+use chrono::{DateTime, Local};
+use ratatui::widgets::ListState;
+use regex::RegexBuilder;
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    ops::Range,
+};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::storage::SessionStore;
+
+// Full-text search over every saved session. The sessions are read once, when
+// search is first used, into an inverted index from each word to the sessions
+// containing it. A query's words then only have to be looked up, so results
+// update on every keystroke even with a large archive.
+//
+// Regex queries can't use the index and scan the texts instead, which are
+// kept in memory for the snippets anyway.
+
+/// At most this many sessions are listed, newest first.
+pub const MAX_RESULTS: usize = 200;
+// Characters of context shown before and after a hit in its snippet
+const SNIPPET_BEFORE_CHARS: usize = 20;
+const SNIPPET_AFTER_CHARS: usize = 40;
+
+struct Document {
+    name: String,
+    saved_at: Option<DateTime<Local>>,
+    text: String,
+}
+
+#[derive(Default)]
+pub struct SearchIndex {
+    // Removed documents leave a `None` behind, so ids stay valid
+    documents: Vec<Option<Document>>,
+    ids: HashMap<String, usize>,
+    terms: BTreeMap<String, BTreeSet<usize>>,
+}
+
+/// A session matching a query.
+pub struct SearchHit {
+    pub name: String,
+    pub saved_at: Option<DateTime<Local>>,
+    /// Byte ranges of the session's text that matched.
+    pub matches: Vec<Range<usize>>,
+    /// The first match with some text around it, on one line.
+    pub snippet: String,
+    /// Byte range of the match in `snippet`.
+    pub snippet_match: Range<usize>,
+}
+
+impl SearchIndex {
+    /// Reads and indexes every session in `store`.
+    pub fn build(store: &dyn SessionStore) -> Self {
+        let mut index = Self::default();
+        for entry in store.list() {
+            if let Ok((_, text)) = store.load(&entry.name) {
+                index.insert(&entry.name, entry.saved_at(), text);
+            }
+        }
+        index
+    }
+
+    /// Adds a session, replacing what was indexed under `name` before.
+    pub fn insert(&mut self, name: &str, saved_at: Option<DateTime<Local>>, text: String) {
+        self.remove(name);
+        let id = self.documents.len();
+        for term in terms(&text) {
+            self.terms.entry(term).or_default().insert(id);
+        }
+        self.documents.push(Some(Document {
+            name: name.to_string(),
+            saved_at,
+            text,
+        }));
+        self.ids.insert(name.to_string(), id);
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        let Some(id) = self.ids.remove(name) else {
+            return;
+        };
+        if let Some(document) = self.documents[id].take() {
+            for term in terms(&document.text) {
+                if let Some(ids) = self.terms.get_mut(&term) {
+                    ids.remove(&id);
+                    if ids.is_empty() {
+                        self.terms.remove(&term);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn rename(&mut self, name: &str, new_name: &str) {
+        let Some(id) = self.ids.remove(name) else {
+            return;
+        };
+        if let Some(document) = &mut self.documents[id] {
+            document.name = new_name.to_string();
+        }
+        self.ids.insert(new_name.to_string(), id);
+    }
+
+    pub fn text(&self, name: &str) -> Option<&str> {
+        let id = *self.ids.get(name)?;
+        self.documents[id].as_ref().map(|d| d.text.as_str())
+    }
+
+    /// Sessions containing every word of `query`, newest first. Words match
+    /// as prefixes, so results show up while a word is still being typed.
+    pub fn search_words(&self, query: &str) -> Vec<SearchHit> {
+        let query_terms = terms(query);
+        let mut candidates: Option<BTreeSet<usize>> = None;
+        for term in &query_terms {
+            let mut ids = BTreeSet::new();
+            for (_, term_ids) in self
+                .terms
+                .range(term.clone()..)
+                .take_while(|(indexed, _)| indexed.starts_with(term.as_str()))
+            {
+                ids.extend(term_ids);
+            }
+            candidates = Some(match candidates {
+                Some(candidates) => &candidates & &ids,
+                None => ids,
+            });
+        }
+        self.hits(candidates.unwrap_or_default().into_iter(), |text| {
+            text.unicode_word_indices()
+                .filter(|(_, word)| {
+                    let word = word.to_lowercase();
+                    query_terms
+                        .iter()
+                        .any(|term| word.starts_with(term.as_str()))
+                })
+                .map(|(start, word)| start..start + word.len())
+                .collect()
+        })
+    }
+
+    /// Sessions matching the regular expression `pattern`, ignoring case,
+    /// newest first.
+    pub fn search_regex(&self, pattern: &str) -> Result<Vec<SearchHit>, regex::Error> {
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(true)
+            .multi_line(true)
+            .build()?;
+        Ok(self.hits(0..self.documents.len(), |text| {
+            regex
+                .find_iter(text)
+                .filter(|m| !m.is_empty())
+                .map(|m| m.range())
+                .collect()
+        }))
+    }
+
+    fn hits(
+        &self,
+        ids: impl Iterator<Item = usize>,
+        find: impl Fn(&str) -> Vec<Range<usize>>,
+    ) -> Vec<SearchHit> {
+        let mut documents: Vec<&Document> = ids
+            .filter_map(|id| self.documents.get(id)?.as_ref())
+            .collect();
+        documents.sort_by(|a, b| b.saved_at.cmp(&a.saved_at).then(b.name.cmp(&a.name)));
+        documents
+            .into_iter()
+            .filter_map(|document| {
+                let matches = find(&document.text);
+                let first = matches.first()?.clone();
+                let (snippet, snippet_match) = snippet(&document.text, first);
+                Some(SearchHit {
+                    name: document.name.clone(),
+                    saved_at: document.saved_at,
+                    matches,
+                    snippet,
+                    snippet_match,
+                })
+            })
+            .take(MAX_RESULTS)
+            .collect()
+    }
+}
+
+/// The search screen: the query being typed and the sessions it matches.
+#[derive(Default)]
+pub struct SearchScreen {
+    pub query: String,
+    /// Treat the query as a regular expression rather than words.
+    pub regex: bool,
+    pub hits: Vec<SearchHit>,
+    /// Why the query couldn't be run, e.g. an invalid regex.
+    pub error: Option<String>,
+    pub list_state: RefCell<ListState>,
+}
+
+impl SearchScreen {
+    /// Runs the query again, after it was edited.
+    pub fn update(&mut self, index: &SearchIndex) {
+        self.error = None;
+        self.hits = if self.query.trim().is_empty() {
+            Vec::new()
+        } else if self.regex {
+            index.search_regex(&self.query).unwrap_or_else(|e| {
+                self.error = Some(e.to_string());
+                Vec::new()
+            })
+        } else {
+            index.search_words(&self.query)
+        };
+        let selected = (!self.hits.is_empty()).then_some(0);
+        *self.list_state.borrow_mut() = ListState::default().with_selected(selected);
+    }
+
+    pub fn selected(&self) -> Option<&SearchHit> {
+        self.hits.get(self.list_state.borrow().selected()?)
+    }
+
+    /// Moves the selection by `hits`, down when positive.
+    pub fn select_by(&mut self, hits: isize) {
+        let Some(selected) = self.list_state.borrow().selected() else {
+            return;
+        };
+        let selected = selected
+            .saturating_add_signed(hits)
+            .min(self.hits.len().saturating_sub(1));
+        self.list_state.borrow_mut().select(Some(selected));
+    }
+}
+
+// The distinct lowercased words of `text`.
+fn terms(text: &str) -> BTreeSet<String> {
+    text.unicode_words().map(str::to_lowercase).collect()
+}
+
+// `hit` with some text on each side, line breaks flattened, and where the hit
+// ended up in it.
+fn snippet(text: &str, hit: Range<usize>) -> (String, Range<usize>) {
+    let start = text[..hit.start]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_BEFORE_CHARS - 1)
+        .map_or(0, |(i, _)| i);
+    let end = text[hit.end..]
+        .char_indices()
+        .nth(SNIPPET_AFTER_CHARS)
+        .map_or(text.len(), |(i, _)| hit.end + i);
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < text.len() { "…" } else { "" };
+    let flatten = |s: &str| s.replace(['\n', '\r', '\t'], " ");
+    let before = format!("{}{}", prefix, flatten(&text[start..hit.start]));
+    let snippet = format!(
+        "{}{}{}{}",
+        before,
+        flatten(&text[hit.clone()]),
+        flatten(&text[hit.end..end]),
+        suffix
+    );
+    let match_start = before.len();
+    (snippet, match_start..match_start + hit.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn index(sessions: &[(&str, &str)]) -> SearchIndex {
+        let mut index = SearchIndex::default();
+        for (day, (name, text)) in sessions.iter().enumerate() {
+            let saved_at = Local
+                .with_ymd_and_hms(2025, 6, day as u32 + 1, 9, 0, 0)
+                .unwrap();
+            index.insert(name, Some(saved_at), text.to_string());
+        }
+        index
+    }
+
+    fn names(hits: &[SearchHit]) -> Vec<&str> {
+        hits.iter().map(|hit| hit.name.as_str()).collect()
+    }
+
+    #[test]
+    fn words_match_as_prefixes_ignoring_case_and_punctuation() {
+        let text = "Über den Fluss, the RIVER's edge.";
+        assert_eq!(
+            terms(text).into_iter().collect::<Vec<_>>(),
+            ["den", "edge", "fluss", "river's", "the", "über"]
+        );
+
+        let index = index(&[("a.txt", text), ("b.txt", "the riverbank")]);
+        assert_eq!(names(&index.search_words("über")), ["a.txt"]);
+        assert_eq!(names(&index.search_words("FLUSS")), ["a.txt"]);
+        // Every word has to match, as a prefix, newest session first
+        assert_eq!(names(&index.search_words("the riv")), ["b.txt", "a.txt"]);
+        assert_eq!(names(&index.search_words("the fluss")), ["a.txt"]);
+        assert!(index.search_words("luss").is_empty());
+    }
+
+    #[test]
+    fn hits_point_at_the_matched_text() {
+        let text = "Straße und ÜBER alles über";
+        let index = index(&[("a.txt", text)]);
+        let hit = &index.search_words("über")[0];
+        let matched: Vec<&str> = hit.matches.iter().map(|m| &text[m.clone()]).collect();
+        assert_eq!(matched, ["ÜBER", "über"]);
+        assert_eq!(&hit.snippet[hit.snippet_match.clone()], "ÜBER");
+    }
+
+    #[test]
+    fn regex_queries_ignore_case_and_report_invalid_patterns() {
+        let index = index(&[("a.txt", "Call me at 555-0100"), ("b.txt", "no numbers")]);
+        let hits = index.search_regex(r"\d{3}-\d{4}").unwrap();
+        assert_eq!(names(&hits), ["a.txt"]);
+        assert_eq!(names(&index.search_regex("^CALL").unwrap()), ["a.txt"]);
+        assert!(index.search_regex("(unclosed").is_err());
+        // Empty matches don't count as hits
+        assert!(index.search_regex("x*").unwrap().is_empty());
+    }
+
+    #[test]
+    fn the_index_follows_saves_renames_and_removals() {
+        let mut index = index(&[("a.txt", "first draft"), ("b.txt", "other words")]);
+
+        index.insert("a.txt", None, "second version".to_string());
+        assert!(index.search_words("first").is_empty());
+        assert_eq!(names(&index.search_words("second")), ["a.txt"]);
+
+        index.rename("a.txt", ".trash/a.txt");
+        assert_eq!(names(&index.search_words("second")), [".trash/a.txt"]);
+        assert_eq!(index.text(".trash/a.txt"), Some("second version"));
+        assert_eq!(index.text("a.txt"), None);
+
+        index.remove(".trash/a.txt");
+        assert!(index.search_words("second").is_empty());
+        assert!(index.search_regex("second").unwrap().is_empty());
+        assert_eq!(names(&index.search_words("words")), ["b.txt"]);
+    }
+}
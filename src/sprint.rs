@@ -0,0 +1,169 @@
+use chrono::{DateTime, Local};
+use std::time::{Duration, Instant};
+
+use crate::app_logic::SessionSummary;
+use crate::config::Config;
+use crate::stats;
+
+// A series of timed sprints with a break countdown between each of them, e.g.
+// 4 × 25 minutes with 5 minute breaks. Each sprint is an ordinary session; the
+// series only decides what comes after it and collects the summaries.
+
+pub struct SprintSeries {
+    pub sprint_count: usize,
+    pub sprint_duration_mins: u64,
+    pub break_duration: Duration,
+    /// Append every sprint to one file, separated by a header, instead of
+    /// saving each to a file of its own.
+    pub single_file: bool,
+    /// Name of the session sprints are appended to, once the first one is saved.
+    pub file: Option<String>,
+    pub completed: Vec<SessionSummary>,
+    pub break_started: Instant,
+    pub started_at: DateTime<Local>,
+}
+
+impl SprintSeries {
+    pub fn new(config: &Config) -> Self {
+        Self {
+            sprint_count: config.sprint_count,
+            sprint_duration_mins: config.sprint_duration_mins,
+            break_duration: Duration::from_secs(config.break_duration_mins * 60),
+            // Daily pages already put the sprints together, one after the other
+            single_file: config.sprint_single_file && !config.daily_page,
+            file: None,
+            completed: Vec::new(),
+            break_started: Instant::now(),
+            started_at: Local::now(),
+        }
+    }
+
+    /// 1-based number of the sprint being written, or the next one during a break.
+    pub fn current_sprint(&self) -> usize {
+        self.completed.len() + 1
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.completed.len() >= self.sprint_count
+    }
+
+    pub fn break_time_left(&self) -> Duration {
+        self.break_duration
+            .saturating_sub(self.break_started.elapsed())
+    }
+
+    /// Header written before a sprint's text when sprints share one file.
+    pub fn separator(&self, saved_at: DateTime<Local>) -> String {
+        let header = format!(
+            "--- Sprint {} of {} ({}) ---\n\n",
+            self.current_sprint(),
+            self.sprint_count,
+            saved_at.format("%H:%M")
+        );
+        if self.completed.is_empty() {
+            header
+        } else {
+            format!("\n\n{}", header)
+        }
+    }
+
+    pub fn total_words(&self) -> usize {
+        self.completed
+            .iter()
+            .map(|summary| summary.word_count)
+            .sum()
+    }
+
+    pub fn total_duration(&self) -> Duration {
+        self.completed.iter().map(|summary| summary.duration).sum()
+    }
+
+    pub fn wpm(&self) -> f64 {
+        stats::words_per_minute(self.total_words(), self.total_duration())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(config: Config) -> SprintSeries {
+        SprintSeries::new(&Config {
+            sprint_count: 3,
+            break_duration_mins: 5,
+            ..config
+        })
+    }
+
+    fn summary(word_count: usize, minutes: u64) -> SessionSummary {
+        SessionSummary {
+            final_filename: String::new(),
+            word_count,
+            wpm: 0.0,
+            duration: Duration::from_secs(minutes * 60),
+            word_goal: None,
+            overtime_words: None,
+            words_added: None,
+            wall_duration: Duration::from_secs(minutes * 60),
+            text_stats: Default::default(),
+            activity: Default::default(),
+        }
+    }
+
+    #[test]
+    fn the_break_counts_down_from_when_it_started() {
+        let mut series = series(Config::default());
+        series.break_started = Instant::now() - Duration::from_secs(60);
+        let left = series.break_time_left();
+        assert!(left <= Duration::from_secs(4 * 60) && left > Duration::from_secs(4 * 60 - 5));
+
+        series.break_started = Instant::now() - Duration::from_secs(6 * 60);
+        assert_eq!(series.break_time_left(), Duration::ZERO);
+    }
+
+    #[test]
+    fn the_series_is_finished_after_its_last_sprint() {
+        let mut series = series(Config::default());
+        assert_eq!(series.current_sprint(), 1);
+        series.completed.push(summary(100, 25));
+        series.completed.push(summary(80, 15));
+        assert_eq!(series.current_sprint(), 3);
+        assert!(!series.is_finished());
+        series.completed.push(summary(0, 0));
+        assert!(series.is_finished());
+
+        assert_eq!(series.total_words(), 180);
+        assert_eq!(series.total_duration(), Duration::from_secs(40 * 60));
+        assert_eq!(series.wpm(), 4.5);
+    }
+
+    #[test]
+    fn single_file_sprints_are_separated_by_a_header() {
+        let mut series = series(Config {
+            sprint_single_file: true,
+            ..Config::default()
+        });
+        assert!(series.single_file);
+        let saved_at = Local::now();
+        let time = saved_at.format("%H:%M");
+        assert_eq!(
+            series.separator(saved_at),
+            format!("--- Sprint 1 of 3 ({}) ---\n\n", time)
+        );
+        series.completed.push(summary(100, 25));
+        assert_eq!(
+            series.separator(saved_at),
+            format!("\n\n--- Sprint 2 of 3 ({}) ---\n\n", time)
+        );
+    }
+
+    #[test]
+    fn daily_pages_keep_sprints_out_of_a_single_file() {
+        let series = series(Config {
+            sprint_single_file: true,
+            daily_page: true,
+            ..Config::default()
+        });
+        assert!(!series.single_file);
+    }
+}
@@ -0,0 +1,232 @@
+use chrono::{DateTime, Datelike, Local, NaiveTime};
+use std::collections::HashSet;
+use std::time::Duration;
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::metadata::SessionEntry;
+
+// Statistics about a session. `TextStats` only looks at the text, so it works
+// just as well for sessions saved long ago; `Activity` follows the typing while
+// a session is written, on the session clock's active time.
+
+#[derive(Clone, Debug, Default)]
+pub struct TextStats {
+    /// Characters as displayed (grapheme clusters), line breaks excluded.
+    pub characters: usize,
+    pub words: usize,
+    pub sentences: usize,
+    /// Non-blank lines, as every Enter starts a new paragraph.
+    pub paragraphs: usize,
+    /// Distinct words, ignoring case and punctuation.
+    pub unique_words: usize,
+    /// Average length of a word in characters, punctuation excluded.
+    pub avg_word_length: f64,
+}
+
+impl TextStats {
+    pub fn from_text(text: &str) -> Self {
+        let words: Vec<String> = text.unicode_words().map(str::to_lowercase).collect();
+        let letters: usize = words.iter().map(|word| word.graphemes(true).count()).sum();
+        let unique_words = words.iter().collect::<HashSet<_>>().len();
+        Self {
+            characters: text.graphemes(true).filter(|g| *g != "\n").count(),
+            words: text.split_whitespace().count(),
+            sentences: text
+                .unicode_sentences()
+                .filter(|sentence| sentence.chars().any(char::is_alphanumeric))
+                .count(),
+            paragraphs: text.lines().filter(|line| !line.trim().is_empty()).count(),
+            unique_words,
+            avg_word_length: if words.is_empty() {
+                0.0
+            } else {
+                letters as f64 / words.len() as f64
+            },
+        }
+    }
+}
+
+/// Typing activity of the session in progress.
+#[derive(Default)]
+pub struct Activity {
+    // Word count at the end of each full minute of active time
+    minute_word_counts: Vec<usize>,
+    last_edit: Duration,
+    longest_pause: Duration,
+}
+
+impl Activity {
+    /// Records an edit made `at` into the session.
+    pub fn record_edit(&mut self, at: Duration) {
+        self.longest_pause = self.longest_pause.max(at.saturating_sub(self.last_edit));
+        self.last_edit = at;
+    }
+
+    /// Samples the word count whenever another full minute has passed. It is
+    /// only counted then, as this runs on every tick.
+    pub fn tick(&mut self, at: Duration, word_count: impl FnOnce() -> usize) {
+        let full_minutes = (at.as_secs() / 60) as usize;
+        if self.minute_word_counts.len() < full_minutes {
+            let word_count = word_count();
+            self.minute_word_counts.resize(full_minutes, word_count);
+        }
+    }
+
+    /// Activity stats of a session that ended `at`, with `word_count` words.
+    pub fn finish(&self, at: Duration, word_count: usize) -> ActivityStats {
+        let mut counts = self.minute_word_counts.clone();
+        // Minutes that weren't sampled (e.g. spent in a popup) end at the final count
+        let full_minutes = (at.as_secs() / 60) as usize;
+        counts.resize(full_minutes.max(counts.len()), word_count);
+        let full_minutes = counts.len();
+        // The last, partial minute still belongs on the graph
+        if !at.as_secs().is_multiple_of(60) || counts.is_empty() {
+            counts.push(word_count);
+        }
+        let mut words_per_minute = Vec::with_capacity(counts.len());
+        let mut previous = 0;
+        for count in counts {
+            words_per_minute.push(count.saturating_sub(previous) as u64);
+            previous = count;
+        }
+        let peak_wpm = words_per_minute[..full_minutes].iter().copied().max();
+        ActivityStats {
+            longest_pause: self.longest_pause.max(at.saturating_sub(self.last_edit)),
+            words_per_minute,
+            peak_wpm,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ActivityStats {
+    /// Longest stretch of active time without typing.
+    pub longest_pause: Duration,
+    /// Words added in each minute of active time, the last one maybe partial.
+    pub words_per_minute: Vec<u64>,
+    /// Most words written in one full minute, `None` for sessions shorter than
+    /// a minute.
+    pub peak_wpm: Option<u64>,
+}
+
+/// Average typing speed over `duration`, 0 for sessions under a second.
+pub fn words_per_minute(word_count: usize, duration: Duration) -> f64 {
+    let secs = duration.as_secs();
+    if secs > 0 {
+        (word_count as f64 / secs as f64) * 60.0
+    } else {
+        0.0
+    }
+}
+
+/// Number of sessions saved since `since` and the words written in them.
+pub fn totals_since(sessions: &[SessionEntry], since: DateTime<Local>) -> (usize, usize) {
+    sessions
+        .iter()
+        .filter(|entry| entry.saved_at().is_some_and(|at| at >= since))
+        .fold((0, 0), |(sessions, words), entry| {
+            let word_count = entry.metadata.as_ref().map_or(0, |m| m.word_count);
+            (sessions + 1, words + word_count)
+        })
+}
+
+/// Midnight at the start of the month `now` is in.
+pub fn start_of_month(now: DateTime<Local>) -> DateTime<Local> {
+    let today = now.date_naive();
+    today
+        .with_day(1)
+        .unwrap_or(today)
+        .and_time(NaiveTime::MIN)
+        .and_local_timezone(Local)
+        .earliest()
+        .unwrap_or(now)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::SessionMetadata;
+    use chrono::TimeZone;
+
+    fn at(month: u32, day: u32, hour: u32, min: u32, sec: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2025, month, day, hour, min, sec)
+            .unwrap()
+    }
+
+    fn entry(ended_at: Option<DateTime<Local>>, word_count: usize) -> SessionEntry {
+        SessionEntry {
+            name: "session.txt".to_string(),
+            metadata: Some(SessionMetadata {
+                started_at: ended_at.map(|at| at - chrono::Duration::minutes(10)),
+                ended_at,
+                word_count,
+                ..SessionMetadata::default()
+            }),
+            first_line: String::new(),
+        }
+    }
+
+    #[test]
+    fn months_start_at_local_midnight_on_the_first() {
+        assert_eq!(start_of_month(at(3, 1, 0, 0, 0)), at(3, 1, 0, 0, 0));
+        assert_eq!(start_of_month(at(3, 31, 23, 59, 59)), at(3, 1, 0, 0, 0));
+        assert_eq!(start_of_month(at(12, 15, 12, 0, 0)), at(12, 1, 0, 0, 0));
+    }
+
+    #[test]
+    fn totals_count_sessions_saved_from_the_start_of_the_month() {
+        let since = start_of_month(at(6, 12, 9, 0, 0));
+        let sessions = [
+            entry(Some(at(5, 31, 23, 59, 59)), 100),
+            // Started in May, saved in June
+            entry(Some(at(6, 1, 0, 0, 0)), 20),
+            entry(Some(at(6, 12, 8, 0, 0)), 3),
+            entry(None, 1000),
+        ];
+        assert_eq!(totals_since(&sessions, since), (2, 23));
+    }
+
+    #[test]
+    fn activity_samples_full_minutes_and_keeps_the_partial_one() {
+        let mut activity = Activity::default();
+        activity.tick(Duration::from_secs(59), || 10);
+        activity.tick(Duration::from_secs(60), || 12);
+        activity.tick(Duration::from_secs(61), || unreachable!());
+        activity.tick(Duration::from_secs(120), || 40);
+
+        let stats = activity.finish(Duration::from_secs(150), 45);
+        assert_eq!(stats.words_per_minute, [12, 28, 5]);
+        assert_eq!(stats.peak_wpm, Some(28));
+
+        let stats = activity.finish(Duration::from_secs(120), 40);
+        assert_eq!(stats.words_per_minute, [12, 28]);
+    }
+
+    #[test]
+    fn activity_of_a_short_session_has_no_peak() {
+        let mut activity = Activity::default();
+        activity.record_edit(Duration::from_secs(5));
+        activity.record_edit(Duration::from_secs(20));
+        let stats = activity.finish(Duration::from_secs(30), 7);
+        assert_eq!(stats.words_per_minute, [7]);
+        assert_eq!(stats.peak_wpm, None);
+        assert_eq!(stats.longest_pause, Duration::from_secs(15));
+    }
+
+    #[test]
+    fn words_per_minute_is_zero_without_a_full_second() {
+        assert_eq!(words_per_minute(50, Duration::from_secs(120)), 25.0);
+        assert_eq!(words_per_minute(50, Duration::from_millis(999)), 0.0);
+    }
+
+    #[test]
+    fn text_stats_count_sentences_paragraphs_and_unique_words() {
+        let stats = TextStats::from_text("The cat sat. The cat ran!\n\nCafé au lait?");
+        assert_eq!(stats.words, 9);
+        assert_eq!(stats.sentences, 3);
+        assert_eq!(stats.paragraphs, 2);
+        assert_eq!(stats.unique_words, 7);
+        assert_eq!(stats.characters, 38);
+    }
+}
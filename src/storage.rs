@@ -0,0 +1,715 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::config::{self, OUTPUT_DIR};
+use crate::draft;
+use crate::index::{self, SessionIndex};
+use crate::metadata::{self, MetadataFormat, SessionEntry, SessionMetadata};
+use crate::naming::{self, NameFields, Subdirectories};
+
+// Where saved sessions live. `App` only talks to a `SessionStore`, which names
+// sessions by a `/` separated name such as `2025/06/morning.txt`; the store
+// decides what that maps to. `DirStore` keeps them as files in the sessions
+// directory, `MemoryStore` keeps them in memory for tests.
+//
+// Deleted sessions go to the trash first: they are renamed into `.trash/`,
+// which stores leave out of `list`, and can be renamed back from there until
+// they are deleted for good.
+
+const MIGRATION_DECLINED_FILE: &str = "migration-declined";
+const TRASH_DIR: &str = ".trash";
+
+/// Name of `name` once moved to the trash.
+pub fn trash_name(name: &str) -> String {
+    format!("{}/{}", TRASH_DIR, name)
+}
+
+/// Name a session in the trash had before it was moved there, `None` for
+/// sessions that aren't in the trash.
+pub fn untrashed_name(name: &str) -> Option<&str> {
+    name.strip_prefix(TRASH_DIR)?.strip_prefix('/')
+}
+
+// --- Storage Errors ---
+#[derive(Debug)]
+pub enum StorageError {
+    CreateDir { path: PathBuf, source: io::Error },
+    Write { path: PathBuf, source: io::Error },
+    Read { path: PathBuf, source: io::Error },
+    Delete { path: PathBuf, source: io::Error },
+    Rename { path: PathBuf, source: io::Error },
+}
+
+impl StorageError {
+    pub fn path(&self) -> &PathBuf {
+        match self {
+            StorageError::CreateDir { path, .. }
+            | StorageError::Write { path, .. }
+            | StorageError::Read { path, .. }
+            | StorageError::Delete { path, .. }
+            | StorageError::Rename { path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::CreateDir { path, source } => {
+                write!(f, "Failed to create {}: {}", path.display(), source)
+            }
+            StorageError::Write { path, source } => {
+                write!(f, "Failed to write {}: {}", path.display(), source)
+            }
+            StorageError::Read { path, source } => {
+                write!(f, "Failed to read {}: {}", path.display(), source)
+            }
+            StorageError::Delete { path, source } => {
+                write!(f, "Failed to delete {}: {}", path.display(), source)
+            }
+            StorageError::Rename { path, source } => {
+                write!(f, "Failed to rename {}: {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl Error for StorageError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            StorageError::CreateDir { source, .. }
+            | StorageError::Write { source, .. }
+            | StorageError::Read { source, .. }
+            | StorageError::Delete { source, .. }
+            | StorageError::Rename { source, .. } => Some(source),
+        }
+    }
+}
+
+// --- Session Stores ---
+pub trait SessionStore {
+    /// Saves a session under `name`, replacing any session saved there before.
+    fn save(
+        &mut self,
+        name: &str,
+        text: &str,
+        metadata: &SessionMetadata,
+    ) -> Result<(), StorageError>;
+
+    /// Every saved session, newest first. Sessions in the trash aren't listed.
+    fn list(&self) -> Vec<SessionEntry>;
+
+    /// Every session in the trash, newest first.
+    fn list_trash(&self) -> Vec<SessionEntry>;
+
+    /// A session's metadata, if it has any, and its text.
+    fn load(&self, name: &str) -> Result<(Option<SessionMetadata>, String), StorageError>;
+
+    fn delete(&mut self, name: &str) -> Result<(), StorageError>;
+
+    /// Moves a session to `new_name`, which must not be taken.
+    fn rename(&mut self, name: &str, new_name: &str) -> Result<(), StorageError>;
+
+    /// Whether `name` is taken, so new sessions never replace existing ones.
+    fn exists(&self, name: &str) -> bool;
+
+    /// Where a session is stored, as shown to the user.
+    fn location(&self, name: &str) -> String {
+        name.to_string()
+    }
+
+    /// Catches up with changes made to the storage outside the app.
+    fn refresh(&mut self) -> Result<(), StorageError> {
+        Ok(())
+    }
+}
+
+/// Sessions stored as files in a directory (and its subdirectories), with
+/// their metadata written in the configured format.
+pub struct DirStore {
+    dir: PathBuf,
+    format: MetadataFormat,
+    index: SessionIndex,
+}
+
+impl DirStore {
+    pub fn open(dir: &Path, format: MetadataFormat) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            format,
+            index: SessionIndex::open(dir),
+        }
+    }
+
+    fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+
+    // Removes the directories `path` was in that are now empty, up to the
+    // sessions directory, so renamed and deleted sessions leave nothing behind.
+    fn remove_empty_dirs(&self, path: &Path) {
+        for dir in path.ancestors().skip(1) {
+            if dir == self.dir || !dir.starts_with(&self.dir) || fs::remove_dir(dir).is_err() {
+                break;
+            }
+        }
+    }
+}
+
+impl SessionStore for DirStore {
+    fn save(
+        &mut self,
+        name: &str,
+        text: &str,
+        metadata: &SessionMetadata,
+    ) -> Result<(), StorageError> {
+        let path = self.path(name);
+        write_session(&path, text, metadata, self.format)?;
+        // The index is only a cache, it catches up on the next start if this fails
+        let _ = self
+            .index
+            .record(&path, metadata, &metadata::first_line(text));
+        Ok(())
+    }
+
+    fn list(&self) -> Vec<SessionEntry> {
+        self.index.sessions()
+    }
+
+    // The trash isn't indexed, it is read whenever it is listed
+    fn list_trash(&self) -> Vec<SessionEntry> {
+        let mut files = Vec::new();
+        index::list_session_files(&self.path(TRASH_DIR), &mut files);
+        let mut entries: Vec<SessionEntry> = files
+            .into_iter()
+            .filter_map(|(path, modified)| {
+                let name = index::relative_name(&self.dir, &path)?;
+                let (metadata, first_line) = index::read_or_derive_metadata(&path, modified);
+                Some(SessionEntry {
+                    name,
+                    metadata: Some(metadata),
+                    first_line,
+                })
+            })
+            .collect();
+        metadata::sort_newest_first(&mut entries);
+        entries
+    }
+
+    fn load(&self, name: &str) -> Result<(Option<SessionMetadata>, String), StorageError> {
+        let path = self.path(name);
+        metadata::read_session(&path).map_err(|source| StorageError::Read { path, source })
+    }
+
+    fn delete(&mut self, name: &str) -> Result<(), StorageError> {
+        let path = self.path(name);
+        // The sidecar goes first, a session left without it is still whole
+        let sidecar = metadata::sidecar_path(&path);
+        if sidecar.exists() {
+            fs::remove_file(&sidecar).map_err(|source| StorageError::Delete {
+                path: sidecar,
+                source,
+            })?;
+        }
+        fs::remove_file(&path).map_err(|source| StorageError::Delete {
+            path: path.clone(),
+            source,
+        })?;
+        let _ = self.index.remove(&path);
+        self.remove_empty_dirs(&path);
+        Ok(())
+    }
+
+    fn rename(&mut self, name: &str, new_name: &str) -> Result<(), StorageError> {
+        let path = self.path(name);
+        let new_path = self.path(new_name);
+        if self.exists(new_name) {
+            return Err(StorageError::Rename {
+                path,
+                source: io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} already exists", new_name),
+                ),
+            });
+        }
+        create_parent_dir(&new_path)?;
+        fs::rename(&path, &new_path).map_err(|source| StorageError::Rename {
+            path: path.clone(),
+            source,
+        })?;
+        let sidecar = metadata::sidecar_path(&path);
+        if sidecar.exists() {
+            fs::rename(&sidecar, metadata::sidecar_path(&new_path)).map_err(|source| {
+                StorageError::Rename {
+                    path: sidecar,
+                    source,
+                }
+            })?;
+        }
+        let _ = self.index.rename(&path, &new_path);
+        self.remove_empty_dirs(&path);
+        Ok(())
+    }
+
+    fn exists(&self, name: &str) -> bool {
+        let path = self.path(name);
+        path.exists() || metadata::sidecar_path(&path).exists()
+    }
+
+    fn location(&self, name: &str) -> String {
+        self.path(name).display().to_string()
+    }
+
+    fn refresh(&mut self) -> Result<(), StorageError> {
+        self.index.rebuild().map_err(|source| StorageError::Read {
+            path: self.dir.clone(),
+            source,
+        })
+    }
+}
+
+/// Sessions kept in memory only, for tests.
+#[derive(Default)]
+pub struct MemoryStore {
+    sessions: HashMap<String, (SessionMetadata, String)>,
+    /// Makes every save fail, as a full or read-only disk would.
+    pub fail_saves: bool,
+}
+
+impl MemoryStore {
+    fn entries(&self, listed: impl Fn(&str) -> bool) -> Vec<SessionEntry> {
+        let mut entries: Vec<SessionEntry> = self
+            .sessions
+            .iter()
+            .filter(|(name, _)| listed(name))
+            .map(|(name, (metadata, text))| SessionEntry {
+                name: name.clone(),
+                metadata: Some(metadata.clone()),
+                first_line: metadata::first_line(text),
+            })
+            .collect();
+        metadata::sort_newest_first(&mut entries);
+        entries
+    }
+
+    fn not_found(name: &str) -> io::Error {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no session named {}", name),
+        )
+    }
+}
+
+impl SessionStore for MemoryStore {
+    fn save(
+        &mut self,
+        name: &str,
+        text: &str,
+        metadata: &SessionMetadata,
+    ) -> Result<(), StorageError> {
+        if self.fail_saves {
+            return Err(StorageError::Write {
+                path: PathBuf::from(name),
+                source: io::Error::new(io::ErrorKind::StorageFull, "no space left"),
+            });
+        }
+        self.sessions
+            .insert(name.to_string(), (metadata.clone(), text.to_string()));
+        Ok(())
+    }
+
+    fn list(&self) -> Vec<SessionEntry> {
+        self.entries(|name| untrashed_name(name).is_none())
+    }
+
+    fn list_trash(&self) -> Vec<SessionEntry> {
+        self.entries(|name| untrashed_name(name).is_some())
+    }
+
+    fn load(&self, name: &str) -> Result<(Option<SessionMetadata>, String), StorageError> {
+        self.sessions
+            .get(name)
+            .map(|(metadata, text)| (Some(metadata.clone()), text.clone()))
+            .ok_or_else(|| StorageError::Read {
+                path: PathBuf::from(name),
+                source: Self::not_found(name),
+            })
+    }
+
+    fn delete(&mut self, name: &str) -> Result<(), StorageError> {
+        self.sessions
+            .remove(name)
+            .map(|_| ())
+            .ok_or_else(|| StorageError::Delete {
+                path: PathBuf::from(name),
+                source: Self::not_found(name),
+            })
+    }
+
+    fn rename(&mut self, name: &str, new_name: &str) -> Result<(), StorageError> {
+        if self.exists(new_name) {
+            return Err(StorageError::Rename {
+                path: PathBuf::from(name),
+                source: io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!("{} already exists", new_name),
+                ),
+            });
+        }
+        let session = self
+            .sessions
+            .remove(name)
+            .ok_or_else(|| StorageError::Rename {
+                path: PathBuf::from(name),
+                source: Self::not_found(name),
+            })?;
+        self.sessions.insert(new_name.to_string(), session);
+        Ok(())
+    }
+
+    fn exists(&self, name: &str) -> bool {
+        self.sessions.contains_key(name)
+    }
+}
+
+// --- Session Files ---
+
+/// Saves a session outside of any store, to `path` or, when it is a directory,
+/// to a new file in it named after `template`.
+pub fn save_to_path(
+    path: &Path,
+    template: &str,
+    fields: &NameFields,
+    metadata: &SessionMetadata,
+    format: MetadataFormat,
+) -> Result<PathBuf, StorageError> {
+    let path = if path.is_dir() {
+        naming::session_path(path, template, Subdirectories::None, fields)
+    } else {
+        path.to_path_buf()
+    };
+    write_session(&path, fields.text, metadata, format)?;
+    Ok(path)
+}
+
+/// Writes a session file along with its metadata, in `format`.
+fn write_session(
+    path: &Path,
+    text: &str,
+    metadata: &SessionMetadata,
+    format: MetadataFormat,
+) -> Result<(), StorageError> {
+    let sidecar = metadata::sidecar_path(path);
+    match format {
+        MetadataFormat::Frontmatter => {
+            write_text_file(path, &metadata::with_frontmatter(metadata, text))?
+        }
+        MetadataFormat::Sidecar => {
+            write_text_file(path, text)?;
+            return write_text_file(&sidecar, &metadata::to_json(metadata));
+        }
+        MetadataFormat::Off => write_text_file(path, text)?,
+    }
+    // A sidecar from before the format was changed would be read instead of
+    // the frontmatter, and no longer matches the text either way
+    if sidecar.exists() {
+        let _ = fs::remove_file(&sidecar);
+    }
+    Ok(())
+}
+
+// Sessions are rewritten in place when continued or added to, so a failed
+// write must never leave the earlier text truncated.
+fn write_text_file(path: &Path, text: &str) -> Result<(), StorageError> {
+    create_parent_dir(path)?;
+    draft::write_atomic(path, text).map_err(|source| StorageError::Write {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn create_parent_dir(path: &Path) -> Result<(), StorageError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|source| StorageError::CreateDir {
+            path: parent.to_path_buf(),
+            source,
+        })?;
+    }
+    Ok(())
+}
+
+// --- Legacy Sessions ---
+
+/// Moves every file in `from` into `to`, never replacing one already there.
+/// Returns how many were moved and what went wrong with the others.
+pub fn move_sessions(from: &Path, to: &Path) -> (usize, Vec<String>) {
+    let mut errors = Vec::new();
+    let mut moved = 0;
+
+    match (fs::create_dir_all(to), fs::read_dir(from)) {
+        (Err(e), _) => errors.push(format!("Failed to create {}: {}", to.display(), e)),
+        (_, Err(e)) => errors.push(format!("Failed to read {}: {}", from.display(), e)),
+        (Ok(()), Ok(entries)) => {
+            for entry in entries.filter_map(|e| e.ok()) {
+                if !entry.file_type().is_ok_and(|ft| ft.is_file()) {
+                    continue;
+                }
+                let target = to.join(entry.file_name());
+                let result = if target.exists() {
+                    Err(io::Error::new(
+                        io::ErrorKind::AlreadyExists,
+                        "a session with this name already exists",
+                    ))
+                } else {
+                    move_file(&entry.path(), &target)
+                };
+                match result {
+                    Ok(()) => moved += 1,
+                    Err(e) => errors.push(format!("{}: {}", entry.path().display(), e)),
+                }
+            }
+        }
+    }
+    // Only succeeds once the directory is empty, so leftovers stay visible.
+    let _ = fs::remove_dir(from);
+    (moved, errors)
+}
+
+// `fs::rename` fails across filesystems, so fall back to copy-then-delete.
+fn move_file(from: &Path, to: &Path) -> io::Result<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    fs::copy(from, to)?;
+    fs::remove_file(from)
+}
+
+/// The `./freewrite_sessions` left behind by older versions, unless it is the
+/// sessions directory itself or moving it was declined before.
+pub fn find_legacy_sessions_dir(sessions_dir: &Path) -> Option<PathBuf> {
+    let legacy_dir = std::path::absolute(OUTPUT_DIR).ok()?;
+    legacy_dir_to_offer(
+        legacy_dir,
+        sessions_dir,
+        migration_declined_file().as_deref(),
+    )
+}
+
+fn legacy_dir_to_offer(
+    legacy_dir: PathBuf,
+    sessions_dir: &Path,
+    declined_file: Option<&Path>,
+) -> Option<PathBuf> {
+    if !legacy_dir.is_dir() || same_dir(&legacy_dir, sessions_dir) {
+        return None;
+    }
+    let declined = declined_file
+        .and_then(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    if declined
+        .lines()
+        .any(|line| Path::new(line) == legacy_dir.as_path())
+    {
+        return None;
+    }
+    Some(legacy_dir)
+}
+
+/// Remembers not to offer moving `legacy_dir` again.
+pub fn decline_migration(legacy_dir: &Path) {
+    if let Some(marker) = migration_declined_file() {
+        record_declined(&marker, legacy_dir);
+    }
+}
+
+fn record_declined(marker: &Path, legacy_dir: &Path) {
+    let mut declined = fs::read_to_string(marker).unwrap_or_default();
+    declined.push_str(&format!("{}\n", legacy_dir.display()));
+    if let Some(parent) = marker.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let _ = fs::write(marker, declined);
+}
+
+fn migration_declined_file() -> Option<PathBuf> {
+    config::data_dir().map(|dir| dir.join(MIGRATION_DECLINED_FILE))
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_files(dir: &Path, files: &[(&str, &str)]) {
+        for (name, text) in files {
+            let path = dir.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, text).unwrap();
+        }
+    }
+
+    fn metadata(word_count: usize) -> SessionMetadata {
+        SessionMetadata {
+            word_count,
+            ..SessionMetadata::default()
+        }
+    }
+
+    fn names(entries: &[SessionEntry]) -> Vec<&str> {
+        let mut names: Vec<&str> = entries.iter().map(|entry| entry.name.as_str()).collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn dir_store_saves_loads_and_renames_sessions_with_their_sidecars() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        let mut store = DirStore::open(dir, MetadataFormat::Sidecar);
+        store.save("a.txt", "Hello there", &metadata(2)).unwrap();
+        store.save("2025/b.txt", "Second", &metadata(1)).unwrap();
+        assert_eq!(names(&store.list()), ["2025/b.txt", "a.txt"]);
+        assert!(dir.join("a.json").exists());
+
+        let (loaded, text) = store.load("a.txt").unwrap();
+        assert_eq!(text, "Hello there");
+        assert_eq!(loaded.unwrap().word_count, 2);
+
+        store.rename("a.txt", "notes/c.txt").unwrap();
+        assert!(!store.exists("a.txt"));
+        assert!(!dir.join("a.json").exists());
+        assert!(dir.join("notes/c.json").exists());
+        assert_eq!(store.load("notes/c.txt").unwrap().0.unwrap().word_count, 2);
+        assert_eq!(names(&store.list()), ["2025/b.txt", "notes/c.txt"]);
+        assert!(store.rename("2025/b.txt", "notes/c.txt").is_err());
+    }
+
+    #[test]
+    fn dir_store_lists_the_trash_apart_from_the_sessions() {
+        let temp = TempDir::new().unwrap();
+        let mut store = DirStore::open(temp.path(), MetadataFormat::Sidecar);
+        store.save("a.txt", "kept", &metadata(1)).unwrap();
+        store.save("b.txt", "thrown away", &metadata(2)).unwrap();
+        store.rename("b.txt", &trash_name("b.txt")).unwrap();
+
+        assert_eq!(names(&store.list()), ["a.txt"]);
+        let trash = store.list_trash();
+        assert_eq!(names(&trash), [".trash/b.txt"]);
+        assert_eq!(trash[0].metadata.as_ref().unwrap().word_count, 2);
+        assert_eq!(trash[0].first_line, "thrown away");
+    }
+
+    #[test]
+    fn dir_store_removes_directories_left_empty() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        let mut store = DirStore::open(dir, MetadataFormat::Sidecar);
+        store.save("2025/06/a.txt", "one", &metadata(1)).unwrap();
+        store.save("2025/07/b.txt", "two", &metadata(1)).unwrap();
+
+        store.delete("2025/06/a.txt").unwrap();
+        assert!(!store.exists("2025/06/a.txt"));
+        assert!(!dir.join("2025/06").exists());
+        assert!(dir.join("2025/07").exists());
+        store.rename("2025/07/b.txt", "b.txt").unwrap();
+        assert!(!dir.join("2025").exists());
+        assert!(dir.exists());
+        assert!(store.delete("2025/07/b.txt").is_err());
+    }
+
+    #[test]
+    fn switching_to_frontmatter_drops_the_stale_sidecar() {
+        let temp = TempDir::new().unwrap();
+        let dir = temp.path();
+        let sidecar = dir.join("a.json");
+        DirStore::open(dir, MetadataFormat::Sidecar)
+            .save("a.txt", "one", &metadata(1))
+            .unwrap();
+        assert!(sidecar.exists());
+
+        let mut store = DirStore::open(dir, MetadataFormat::Frontmatter);
+        store.save("a.txt", "one two", &metadata(2)).unwrap();
+        assert!(!sidecar.exists());
+        assert_eq!(
+            metadata::read_metadata(&dir.join("a.txt"))
+                .unwrap()
+                .word_count,
+            2
+        );
+        let (loaded, text) = store.load("a.txt").unwrap();
+        assert_eq!((loaded.unwrap().word_count, text.as_str()), (2, "one two"));
+    }
+
+    #[test]
+    fn moving_sessions_empties_and_removes_the_legacy_dir() {
+        let temp = TempDir::new().unwrap();
+        let (from, to) = (temp.path().join("legacy"), temp.path().join("sessions"));
+        write_files(&from, &[("a.txt", "one"), ("b.txt", "two")]);
+
+        assert_eq!(move_sessions(&from, &to), (2, Vec::new()));
+        assert!(!from.exists());
+        assert_eq!(fs::read_to_string(to.join("b.txt")).unwrap(), "two");
+    }
+
+    #[test]
+    fn moving_sessions_never_replaces_one_already_there() {
+        let temp = TempDir::new().unwrap();
+        let (from, to) = (temp.path().join("legacy"), temp.path().join("sessions"));
+        write_files(&from, &[("a.txt", "one"), ("b.txt", "old two")]);
+        write_files(&from, &[("notes/c.txt", "folders are left alone")]);
+        write_files(&to, &[("b.txt", "new two")]);
+
+        let (moved, errors) = move_sessions(&from, &to);
+        assert_eq!(moved, 1);
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("b.txt"), "{}", errors[0]);
+        // What couldn't be moved stays where it was, untouched
+        assert_eq!(fs::read_to_string(from.join("b.txt")).unwrap(), "old two");
+        assert_eq!(fs::read_to_string(to.join("b.txt")).unwrap(), "new two");
+        assert!(from.join("notes/c.txt").exists());
+        assert!(to.join("a.txt").exists() && !from.join("a.txt").exists());
+    }
+
+    #[test]
+    fn moving_sessions_reports_a_legacy_dir_it_cant_read() {
+        let temp = TempDir::new().unwrap();
+        let (moved, errors) = move_sessions(&temp.path().join("gone"), temp.path());
+        assert_eq!(moved, 0);
+        assert!(errors[0].starts_with("Failed to read"), "{}", errors[0]);
+    }
+
+    #[test]
+    fn declined_legacy_dirs_arent_offered_again() {
+        let temp = TempDir::new().unwrap();
+        let sessions_dir = temp.path().join("sessions");
+        let (legacy, other) = (temp.path().join("legacy"), temp.path().join("other"));
+        fs::create_dir_all(&sessions_dir).unwrap();
+        fs::create_dir_all(&legacy).unwrap();
+        fs::create_dir_all(&other).unwrap();
+        let marker = temp.path().join("data/migration-declined");
+        let offered =
+            |dir: &Path| legacy_dir_to_offer(dir.to_path_buf(), &sessions_dir, Some(&marker));
+
+        assert_eq!(offered(&legacy), Some(legacy.clone()));
+        assert_eq!(offered(&sessions_dir), None);
+        assert_eq!(offered(&temp.path().join("missing")), None);
+
+        record_declined(&marker, &legacy);
+        assert_eq!(offered(&legacy), None);
+        assert_eq!(offered(&other), Some(other.clone()));
+        record_declined(&marker, &other);
+        assert_eq!(offered(&legacy), None);
+        assert_eq!(offered(&other), None);
+    }
+}
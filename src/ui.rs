@@ -1,23 +1,31 @@
 use chrono::Local;
 use ratatui::{
     Frame,
-    layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Position, Rect},
     style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
+    widgets::{
+        Block, Borders, Clear, LineGauge, List, ListItem, ListState, Paragraph, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, Sparkline, Wrap,
+    },
 };
+use std::{ops::Range, time::Duration};
+use unicode_width::UnicodeWidthStr;
 
-use crate::app_logic::{App, AppState, PanelFocus};
-use crate::config::{
-    ACCENT_COLOR, DEFAULT_SESSION_DURATION_MINS, FILE_ITEM_COLOR, FOCUS_BORDER_COLOR, INFO_COLOR,
-    NORMAL_BORDER_COLOR, SELECTED_ITEM_BG, SELECTED_ITEM_FG, SUCCESS_COLOR, TITLE_COLOR,
-};
+use crate::app_logic::{App, AppState, PanelFocus, SessionAction, SessionSummary};
+use crate::browser::{BrowserRow, SortBy};
+use crate::config::{Config, ContinueMode, DEFAULT_WORD_GOAL, Theme, TimerEnd};
+use crate::layout::{TextLayout, VisualLine};
+use crate::metadata::{SessionMetadata, SessionMode};
+use crate::search::MAX_RESULTS;
+use crate::sprint::SprintSeries;
+use crate::storage;
 
-// ... (rest of your UI rendering functions) ...
-// The function bodies will use App, AppState, PanelFocus, and the constants directly.
-// For example, instead of `Color::White`, use `SELECTED_ITEM_FG` for consistency.
+// The render functions take colors from the runtime `Theme` rather than
+// hard-coding them, e.g. `theme.selected_item_fg` instead of `Color::White`.
 
-pub fn ui(frame: &mut Frame, app: &App) {
+pub fn ui(frame: &mut Frame, app: &App, config: &Config) {
+    let theme = &config.theme;
     let size = frame.area();
 
     let outer_margin_chunks = Layout::default()
@@ -41,27 +49,72 @@ pub fn ui(frame: &mut Frame, app: &App) {
     let inner_rect = horizontal_margin_chunks[1];
 
     match app.current_state {
-        AppState::MainMenu => render_main_menu(frame, app, inner_rect, outer_margin_chunks[2]),
+        AppState::MainMenu | AppState::RecentFilter => {
+            render_main_menu(frame, app, theme, inner_rect, outer_margin_chunks[2])
+        }
         AppState::Freewrite => {
-            render_freewrite_session(frame, app, inner_rect, outer_margin_chunks[2])
+            render_freewrite_session(frame, app, theme, inner_rect, outer_margin_chunks[2])
         }
-        AppState::SessionEnd {
-            ref final_filename,
-            word_count,
-            wpm,
-        } => {
-            render_session_end(
-                frame,
-                final_filename,
-                word_count,
-                wpm,
-                inner_rect,
-                outer_margin_chunks[2],
-            );
+        AppState::SessionEnd { ref summary } => {
+            render_session_end(frame, theme, summary, inner_rect, outer_margin_chunks[2]);
+        }
+        AppState::SprintBreak => {
+            render_sprint_break(frame, app, theme, inner_rect, outer_margin_chunks[2]);
+        }
+        AppState::SprintSeriesEnd { ref series } => {
+            render_sprint_series_end(frame, theme, series, inner_rect, outer_margin_chunks[2]);
+        }
+        AppState::Search => {
+            render_search(frame, app, theme, inner_rect, outer_margin_chunks[2]);
+        }
+        AppState::SessionBrowser => {
+            render_session_browser(frame, app, theme, inner_rect, outer_margin_chunks[2]);
+        }
+        AppState::OpenSession { ref name } => {
+            render_main_menu(frame, app, theme, inner_rect, outer_margin_chunks[2]);
+            render_open_session_popup(frame, app, theme, name);
+        }
+        AppState::SessionAction { action, ref name } => {
+            render_session_browser(frame, app, theme, inner_rect, outer_margin_chunks[2]);
+            render_session_action_popup(frame, app, theme, action, name);
+        }
+        AppState::SessionViewer => {
+            render_session_viewer(frame, app, theme, inner_rect, outer_margin_chunks[2]);
         }
         AppState::CustomDurationInput => {
-            render_main_menu(frame, app, inner_rect, outer_margin_chunks[2]);
-            render_custom_duration_input_popup(frame, app);
+            render_main_menu(frame, app, theme, inner_rect, outer_margin_chunks[2]);
+            render_custom_duration_input_popup(frame, app, config);
+        }
+        AppState::CustomWordGoalInput => {
+            render_main_menu(frame, app, theme, inner_rect, outer_margin_chunks[2]);
+            render_custom_word_goal_input_popup(frame, app, theme);
+        }
+        AppState::Notice {
+            ref title,
+            ref messages,
+        } => {
+            render_main_menu(frame, app, theme, inner_rect, outer_margin_chunks[2]);
+            render_notice_popup(frame, theme, title, messages);
+        }
+        AppState::MigrationPrompt => {
+            render_main_menu(frame, app, theme, inner_rect, outer_margin_chunks[2]);
+            render_migration_prompt_popup(frame, app, theme);
+        }
+        AppState::DraftRecovery => {
+            render_main_menu(frame, app, theme, inner_rect, outer_margin_chunks[2]);
+            render_draft_recovery_popup(frame, app, theme);
+        }
+        AppState::QuitConfirm => {
+            render_freewrite_session(frame, app, theme, inner_rect, outer_margin_chunks[2]);
+            render_quit_confirm_popup(frame, app, theme);
+        }
+        AppState::SaveError => {
+            render_freewrite_session(frame, app, theme, inner_rect, outer_margin_chunks[2]);
+            render_save_error_popup(frame, app, theme);
+        }
+        AppState::AlternatePathInput => {
+            render_freewrite_session(frame, app, theme, inner_rect, outer_margin_chunks[2]);
+            render_alternate_path_input_popup(frame, app, theme);
         }
         AppState::Quitting => {}
     }
@@ -70,6 +123,7 @@ pub fn ui(frame: &mut Frame, app: &App) {
 fn render_main_menu(
     frame: &mut Frame,
     app: &App,
+    theme: &Theme,
     area: ratatui::layout::Rect,
     nav_area: ratatui::layout::Rect,
 ) {
@@ -88,18 +142,18 @@ fn render_main_menu(
         .title(Span::styled(
             " Select Sessions ",
             Style::default()
-                .fg(TITLE_COLOR)
+                .fg(theme.title)
                 .add_modifier(Modifier::BOLD),
         ))
         .border_style(
             Style::default().fg(if let PanelFocus::Sessions = app.panel_focus {
-                FOCUS_BORDER_COLOR
+                theme.focus_border
             } else {
-                NORMAL_BORDER_COLOR
+                theme.normal_border
             }),
         )
         .border_type(ratatui::widgets::BorderType::Rounded)
-        .style(Style::default().fg(SELECTED_ITEM_FG));
+        .style(Style::default().fg(theme.selected_item_fg));
     frame.render_widget(sessions_block.clone(), left_panel_chunks[0]);
     let inner_sessions_area = sessions_block.inner(left_panel_chunks[0]);
 
@@ -111,9 +165,11 @@ fn render_main_menu(
             let style = if i == app.selected_menu_index
                 && matches!(app.panel_focus, PanelFocus::Sessions)
             {
-                Style::default().fg(SELECTED_ITEM_FG).bg(SELECTED_ITEM_BG)
+                Style::default()
+                    .fg(theme.selected_item_fg)
+                    .bg(theme.selected_item_bg)
             } else {
-                Style::default().fg(ACCENT_COLOR)
+                Style::default().fg(theme.accent)
             };
             ListItem::new(item.to_string()).style(style)
         })
@@ -129,41 +185,71 @@ fn render_main_menu(
         .highlight_style(Style::default().add_modifier(Modifier::BOLD));
     frame.render_widget(menu_list, inner_sessions_area);
 
-    let recent_block = Block::default()
+    let recent_title = match &app.recent_filter {
+        Some(filter) => format!(" Recent /{} ", filter),
+        None => " Recent ".to_string(),
+    };
+    let mut recent_block = Block::default()
         .borders(Borders::ALL)
         .title(Span::styled(
-            " Recent ",
+            recent_title,
             Style::default()
-                .fg(TITLE_COLOR)
+                .fg(theme.title)
                 .add_modifier(Modifier::BOLD),
         ))
         .border_style(
             Style::default().fg(if let PanelFocus::Recent = app.panel_focus {
-                FOCUS_BORDER_COLOR
+                theme.focus_border
             } else {
-                NORMAL_BORDER_COLOR
+                theme.normal_border
             }),
         )
         .border_type(ratatui::widgets::BorderType::Rounded)
-        .style(Style::default().fg(SELECTED_ITEM_FG));
+        .style(Style::default().fg(theme.selected_item_fg));
+    if app.recent_filter.is_some() {
+        recent_block = recent_block.title_bottom(
+            Line::from(match app.recent_files.len() {
+                1 => " 1 match ".to_string(),
+                count => format!(" {} matches ", count),
+            })
+            .right_aligned(),
+        );
+    }
     frame.render_widget(recent_block.clone(), left_panel_chunks[1]);
     let inner_recent_area = recent_block.inner(left_panel_chunks[1]);
+    if let (AppState::RecentFilter, Some(filter)) = (&app.current_state, &app.recent_filter) {
+        // At the end of the filter, in the panel's title
+        frame.set_cursor_position(Position::new(
+            left_panel_chunks[1].x + " Recent /".len() as u16 + 1 + filter.width() as u16,
+            left_panel_chunks[1].y,
+        ));
+    }
 
-    let recent_items: Vec<ListItem> = app
+    let mut recent_items: Vec<ListItem> = app
         .recent_files
         .iter()
         .enumerate()
-        .map(|(i, file_name)| {
+        .map(|(i, entry)| {
             let style = if i == app.selected_recent_index
                 && matches!(app.panel_focus, PanelFocus::Recent)
             {
-                Style::default().fg(SELECTED_ITEM_FG).bg(SELECTED_ITEM_BG)
+                Style::default()
+                    .fg(theme.selected_item_fg)
+                    .bg(theme.selected_item_bg)
             } else {
-                Style::default().fg(FILE_ITEM_COLOR)
+                Style::default().fg(theme.file_item)
             };
-            ListItem::new(file_name.clone()).style(style)
+            ListItem::new(entry.name.clone()).style(style)
         })
         .collect();
+    if recent_items.is_empty() {
+        let message = if app.recent_filter.is_some() {
+            "No session matches"
+        } else {
+            "There's no recent session"
+        };
+        recent_items.push(ListItem::new(message).style(Style::default().fg(theme.info)));
+    }
 
     let recent_list = List::new(recent_items)
         .block(Block::default())
@@ -173,24 +259,36 @@ fn render_main_menu(
             "  "
         })
         .highlight_style(Style::default().add_modifier(Modifier::BOLD));
-    frame.render_widget(recent_list, inner_recent_area);
+    // Filtered lists can be longer than the panel, the selection keeps in view
+    let mut recent_state = ListState::default().with_selected(Some(app.selected_recent_index));
+    frame.render_stateful_widget(recent_list, inner_recent_area, &mut recent_state);
 
     let right_panel_block = Block::default()
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(NORMAL_BORDER_COLOR))
+        .border_style(Style::default().fg(theme.normal_border))
         .border_type(ratatui::widgets::BorderType::Rounded)
-        .style(Style::default().fg(SELECTED_ITEM_FG));
+        .style(Style::default().fg(theme.selected_item_fg));
     frame.render_widget(right_panel_block.clone(), main_chunks[1]);
     let inner_right_area = right_panel_block.inner(main_chunks[1]);
 
     if let Some(content) = &app.displayed_file_content {
+        let mut preview_area = inner_right_area;
+        if let Some(metadata) = &app.displayed_file_metadata {
+            let [header_area, content_area] =
+                Layout::vertical([Constraint::Length(2), Constraint::Min(0)])
+                    .areas(inner_right_area);
+            let header =
+                Paragraph::new(metadata_summary(metadata)).style(Style::default().fg(theme.info));
+            frame.render_widget(header, header_area);
+            preview_area = content_area;
+        }
         let file_content_paragraph = Paragraph::new(content.as_str())
             .wrap(Wrap { trim: true })
             .scroll((0, 0))
-            .style(Style::default().fg(SELECTED_ITEM_FG));
-        frame.render_widget(file_content_paragraph, inner_right_area);
+            .style(Style::default().fg(theme.selected_item_fg));
+        frame.render_widget(file_content_paragraph, preview_area);
     } else {
-        let ascii_art = vec![
+        let ascii_art = [
             "░█▀▀░█▀▄░█▀▀░█▀▀░█░█░█▀▄░▀█▀░▀█▀░█▀▀░",
             "░█▀▀░█▀▄░█▀▀░█▀▀░█▄█░█▀▄░░█░░░█░░█▀▀░",
             " ▀░░░▀░▀░▀▀▀░▀▀▀░▀░▀░▀░▀░▀▀▀░░▀░░▀▀▀░",
@@ -203,7 +301,7 @@ fn render_main_menu(
             combined_lines.push(Line::from(vec![
                 Span::raw(*s).style(
                     Style::default()
-                        .fg(ACCENT_COLOR)
+                        .fg(theme.accent)
                         .add_modifier(Modifier::BOLD)
                         .add_modifier(Modifier::ITALIC),
                 ),
@@ -214,16 +312,25 @@ fn render_main_menu(
 
         combined_lines.push(Line::from(vec![
             Span::raw("TUI Based tools for free writing without distractions.")
-                .style(Style::default().fg(INFO_COLOR)),
+                .style(Style::default().fg(theme.info)),
         ]));
         combined_lines.push(Line::from(vec![
-            Span::raw(format!(
-                "{}",
-                Local::now().format("%A, %Y-%m-%d %H:%M").to_string()
-            ))
-            .style(Style::default().fg(INFO_COLOR)),
+            Span::raw(Local::now().format("%A, %Y-%m-%d %H:%M").to_string())
+                .style(Style::default().fg(theme.info)),
         ]));
         combined_lines.push(Line::from(Span::raw("")));
+        let (sessions, words) = app.month_totals;
+        if sessions > 0 {
+            combined_lines.push(Line::from(vec![
+                Span::raw(format!(
+                    "This month: {} words in {} session{}",
+                    words,
+                    sessions,
+                    if sessions == 1 { "" } else { "s" }
+                ))
+                .style(Style::default().fg(theme.accent)),
+            ]));
+        }
 
         let title_paragraph = Paragraph::new(combined_lines)
             .alignment(Alignment::Center)
@@ -231,16 +338,23 @@ fn render_main_menu(
         frame.render_widget(title_paragraph, inner_right_area);
     }
 
-    let nav_hint = format!("  [k] up  [j] down  [h] left  [l] right  [Enter] select  [q] quit  ");
+    let nav_hint = if let AppState::RecentFilter = app.current_state {
+        "  Type to filter  [↑] up  [↓] down  [Enter] keep  [Esc] clear  "
+    } else if app.recent_filter.is_some() {
+        "  [k] up  [j] down  [h] left  [l] right  [Enter] select  [/] edit filter  [Esc] clear filter  [q] quit  "
+    } else {
+        "  [k] up  [j] down  [h] left  [l] right  [Enter] select  [/] filter  [b] browse  [f] find  [r] reload  [q] quit  "
+    };
     let nav_paragraph = Paragraph::new(nav_hint)
         .alignment(Alignment::Center)
-        .style(Style::default().fg(INFO_COLOR));
+        .style(Style::default().fg(theme.info));
     frame.render_widget(nav_paragraph, nav_area);
 }
 
 fn render_freewrite_session(
     frame: &mut Frame,
     app: &App,
+    theme: &Theme,
     area: ratatui::layout::Rect,
     nav_area: ratatui::layout::Rect,
 ) {
@@ -249,72 +363,130 @@ fn render_freewrite_session(
         .constraints([Constraint::Length(2), Constraint::Min(0)])
         .split(area);
 
-    let elapsed_seconds = app.start_time.elapsed().as_secs();
-    let time_left_seconds = app
-        .session_duration
-        .as_secs()
-        .saturating_sub(elapsed_seconds);
-    let minutes = time_left_seconds / 60;
-    let seconds = time_left_seconds % 60;
+    let elapsed_seconds = app.clock.active_elapsed().as_secs();
     let word_count = app.get_word_count();
 
-    let status_line = format!(
-        "Press Escape to go back. Time left: {:02}:{:02} | Word Count: {}",
-        minutes, seconds, word_count
-    );
-    let status_paragraph = Paragraph::new(status_line).style(Style::default().fg(SELECTED_ITEM_FG));
-    frame.render_widget(status_paragraph, freewrite_chunks[0]);
+    let mut status_line = if let Some(word_goal) = app.word_goal {
+        format!(
+            "Press Escape to go back. Elapsed: {:02}:{:02} | Word Count: {}/{}",
+            elapsed_seconds / 60,
+            elapsed_seconds % 60,
+            word_count,
+            word_goal
+        )
+    } else if let Some(duration) = app.session_duration
+        && app.in_overtime()
+    {
+        let overtime_seconds = elapsed_seconds.saturating_sub(duration.as_secs());
+        format!(
+            "Press Escape to finish. Overtime: +{:02}:{:02} | Word Count: {}",
+            overtime_seconds / 60,
+            overtime_seconds % 60,
+            word_count
+        )
+    } else if let Some(duration) = app.session_duration {
+        let time_left_seconds = duration.as_secs().saturating_sub(elapsed_seconds);
+        let minutes = time_left_seconds / 60;
+        let seconds = time_left_seconds % 60;
+        format!(
+            "Press Escape to go back. Time left: {:02}:{:02} | Word Count: {}",
+            minutes, seconds, word_count
+        )
+    } else {
+        format!(
+            "Press Escape to finish. Elapsed: {:02}:{:02} | Word Count: {}",
+            elapsed_seconds / 60,
+            elapsed_seconds % 60,
+            word_count
+        )
+    };
+    if let Some(series) = &app.sprint {
+        status_line.push_str(&format!(
+            " | Sprint {} of {}",
+            series.current_sprint(),
+            series.sprint_count
+        ));
+    }
+    if app.clock.is_paused() {
+        status_line.push_str(" | PAUSED, type to resume");
+    }
+    if let Some(error) = &app.autosave_error {
+        status_line.push_str(&format!(" | Autosave failed: {}", error));
+    }
+    let status_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(1), Constraint::Length(1)])
+        .split(freewrite_chunks[0]);
+    let status_paragraph =
+        Paragraph::new(status_line).style(Style::default().fg(theme.selected_item_fg));
+    frame.render_widget(status_paragraph, status_chunks[0]);
+
+    if let Some(word_goal) = app.word_goal {
+        let (label, color) = if app.word_goal_reached() {
+            ("Goal reached! ".to_string(), theme.success)
+        } else {
+            ("Progress ".to_string(), theme.accent)
+        };
+        let progress = LineGauge::default()
+            .ratio((word_count as f64 / word_goal.max(1) as f64).min(1.0))
+            .label(label)
+            .style(Style::default().fg(theme.info))
+            .filled_style(Style::default().fg(color))
+            .unfilled_style(Style::default().fg(theme.normal_border));
+        frame.render_widget(progress, status_chunks[1]);
+    } else if let Some(duration) = app.session_duration
+        && app.in_overtime()
+    {
+        let banner = match app.timer_end {
+            TimerEnd::Grace(grace) => {
+                let ends_in = (duration + grace).as_secs().saturating_sub(elapsed_seconds);
+                format!(
+                    "Time's up! The session ends in {:02}:{:02}",
+                    ends_in / 60,
+                    ends_in % 60
+                )
+            }
+            _ => "Time's up! Keep writing, or press Escape to finish".to_string(),
+        };
+        let banner_paragraph = Paragraph::new(banner).style(
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        );
+        frame.render_widget(banner_paragraph, status_chunks[1]);
+    }
 
     let text_area_block = Block::default()
         .borders(Borders::ALL)
         .title(Span::styled(
             " Writing Area ",
             Style::default()
-                .fg(TITLE_COLOR)
+                .fg(theme.title)
                 .add_modifier(Modifier::BOLD),
         ))
-        .border_style(Style::default().fg(NORMAL_BORDER_COLOR))
+        .border_style(Style::default().fg(theme.normal_border))
         .border_type(ratatui::widgets::BorderType::Rounded)
-        .style(Style::default().fg(SELECTED_ITEM_FG));
+        .style(Style::default().fg(theme.selected_item_fg));
 
     frame.render_widget(text_area_block.clone(), freewrite_chunks[1]);
     let inner_text_area = text_area_block.inner(freewrite_chunks[1]);
 
-    let mut cursor_x: u16 = 0;
-    let mut cursor_y: u16 = 0;
-    let mut total_virtual_lines: u16 = 0;
+    // Rendering and cursor placement share one layout, so the cursor always
+    // lands where the text is actually drawn
+    app.writing_area_width.set(inner_text_area.width);
+    let layout = app.text_layout();
+    let (cursor_x, cursor_y) = layout.cursor_position(&app.text, app.cursor);
 
-    let max_width = inner_text_area.width;
+    // Scroll just enough to keep the cursor's row on screen
+    let scroll_offset_y = cursor_y.saturating_sub(inner_text_area.height.saturating_sub(1));
 
-    for char_in_text in app.text.chars() {
-        if char_in_text == '\n' {
-            total_virtual_lines += 1;
-            cursor_y += 1;
-            cursor_x = 0;
-        } else {
-            cursor_x += 1;
-            if cursor_x >= max_width {
-                total_virtual_lines += 1;
-                cursor_y += 1;
-                cursor_x = 0;
-            }
-        }
-    }
-    if app.text.is_empty() {
-        total_virtual_lines = 1;
-    } else if cursor_x > 0 {
-        total_virtual_lines += 1;
-    }
-
-    let scroll_offset_y = if total_virtual_lines > inner_text_area.height {
-        total_virtual_lines.saturating_sub(inner_text_area.height)
-    } else {
-        0
-    };
-
-    let text_paragraph = Paragraph::new(app.text.as_str())
-        .wrap(Wrap { trim: true })
-        .style(Style::default().fg(SELECTED_ITEM_FG))
+    let lines: Vec<Line> = layout
+        .lines
+        .iter()
+        .map(|line| Line::from(&app.text[line.start..line.end]))
+        .collect();
+    let text_paragraph = Paragraph::new(lines)
+        .style(Style::default().fg(theme.selected_item_fg))
         .scroll((scroll_offset_y, 0));
 
     frame.render_widget(text_paragraph, inner_text_area);
@@ -326,86 +498,977 @@ fn render_freewrite_session(
         inner_text_area.y + final_cursor_y,
     ));
 
-    let nav_hint_freewrite = format!("  [Escape] back to menu  [Ctrl+C] quit  ");
+    let nav_hint_freewrite = if app.append_only {
+        "  [Ctrl+P] pause  [Escape] back to menu  [Ctrl+C] quit  "
+    } else {
+        "  [Arrows] move  [Ctrl+Arrows] jump words  [Home/End] line  [Ctrl+P] pause  [Escape] back to menu  [Ctrl+C] quit  "
+    };
     let nav_paragraph_freewrite = Paragraph::new(nav_hint_freewrite)
         .alignment(Alignment::Center)
-        .style(Style::default().fg(INFO_COLOR));
+        .style(Style::default().fg(theme.info));
     frame.render_widget(nav_paragraph_freewrite, nav_area);
 }
 
 fn render_session_end(
     frame: &mut Frame,
-    final_filename: &str,
-    word_count: usize,
-    wpm: f64,
+    theme: &Theme,
+    summary: &SessionSummary,
     area: ratatui::layout::Rect,
     nav_area: ratatui::layout::Rect,
 ) {
-    let block = Block::default()
+    let mut summary_lines = vec![
+        Line::from(vec![Span::styled(
+            "Freewrite Done!",
+            Style::default()
+                .fg(theme.success)
+                .add_modifier(Modifier::BOLD),
+        )]),
+        Line::from(vec![Span::raw("")]),
+        Line::from(vec![
+            Span::raw("Your writing has been saved to:").style(Style::default().fg(theme.info)),
+        ]),
+        Line::from(vec![Span::styled(
+            summary.final_filename.clone(),
+            Style::default().fg(theme.focus_border),
+        )]),
+        Line::from(vec![Span::raw("")]),
+        Line::from(vec![
+            Span::raw(match summary.words_added {
+                Some(added) => format!("Word Count: {} ({} added)", summary.word_count, added),
+                None => format!("Word Count: {}", summary.word_count),
+            })
+            .style(Style::default().fg(theme.info)),
+        ]),
+        Line::from(vec![
+            Span::raw(format!(
+                "Active Time: {} | Wall Time: {}",
+                format_duration(summary.duration),
+                format_duration(summary.wall_duration)
+            ))
+            .style(Style::default().fg(theme.info)),
+        ]),
+        Line::from(vec![
+            Span::raw(format!("Typing Speed: {:.2} word/minutes", summary.wpm))
+                .style(Style::default().fg(theme.info)),
+        ]),
+    ];
+    if let Some(word_goal) = summary.word_goal {
+        let goal_status = if summary.word_count >= word_goal {
+            "reached".to_string()
+        } else {
+            format!("{} words short", word_goal - summary.word_count)
+        };
+        summary_lines.push(Line::from(vec![
+            Span::raw(format!("Word Goal: {} ({})", word_goal, goal_status))
+                .style(Style::default().fg(theme.info)),
+        ]));
+    }
+    if let Some(overtime_words) = summary.overtime_words {
+        summary_lines.push(Line::from(vec![
+            Span::raw(format!(
+                "In Time: {} words | Overtime: {} words",
+                summary.word_count.saturating_sub(overtime_words),
+                overtime_words
+            ))
+            .style(Style::default().fg(theme.info)),
+        ]));
+    }
+
+    let text_stats = &summary.text_stats;
+    let activity = &summary.activity;
+    summary_lines.extend([
+        Line::from(vec![Span::raw("")]),
+        Line::from(vec![
+            Span::raw(format!(
+                "Characters: {} | Sentences: {} | Paragraphs: {}",
+                text_stats.characters, text_stats.sentences, text_stats.paragraphs
+            ))
+            .style(Style::default().fg(theme.info)),
+        ]),
+        Line::from(vec![
+            Span::raw(format!(
+                "Unique Words: {} | Average Word Length: {:.1}",
+                text_stats.unique_words, text_stats.avg_word_length
+            ))
+            .style(Style::default().fg(theme.info)),
+        ]),
+        Line::from(vec![
+            Span::raw(format!(
+                "Longest Pause: {} | Peak WPM: {}",
+                format_duration(activity.longest_pause),
+                activity
+                    .peak_wpm
+                    .map_or("n/a".to_string(), |wpm| wpm.to_string())
+            ))
+            .style(Style::default().fg(theme.info)),
+        ]),
+        Line::from(vec![Span::raw("")]),
+        Line::from(vec![
+            Span::raw("Words per minute:").style(Style::default().fg(theme.info)),
+        ]),
+    ]);
+    // Rows left empty for the sparkline, drawn over them below
+    let sparkline_row = summary_lines.len() as u16;
+    let sparkline_height = 3;
+    summary_lines.extend((0..sparkline_height).map(|_| Line::from("")));
+    summary_lines.extend([
+        Line::from(vec![Span::raw("")]),
+        Line::from(vec![Span::styled(
+            "Press [Enter] to go back to main menu...",
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )]),
+    ]);
+
+    render_full_screen(
+        frame,
+        theme,
+        " Session Ended ",
+        summary_lines,
+        area,
+        nav_area,
+        "  [Enter] back to menu  [q] quit  ",
+    );
+
+    // Stretch the minutes over up to 40 columns so short sessions stay readable
+    let minutes = activity.words_per_minute.len().max(1);
+    let column_width = (40 / minutes).max(1);
+    let data: Vec<u64> = activity
+        .words_per_minute
+        .iter()
+        .flat_map(|&words| std::iter::repeat_n(words, column_width))
+        .collect();
+    let inner_area = area.inner(Margin::new(1, 1));
+    let sparkline_width = (data.len() as u16).min(inner_area.width);
+    if sparkline_row + sparkline_height <= inner_area.height {
+        let sparkline_area = Rect::new(
+            inner_area.x + (inner_area.width - sparkline_width) / 2,
+            inner_area.y + sparkline_row,
+            sparkline_width,
+            sparkline_height,
+        );
+        let sparkline = Sparkline::default()
+            .data(&data)
+            .style(Style::default().fg(theme.accent));
+        frame.render_widget(sparkline, sparkline_area);
+    }
+}
+
+fn render_session_viewer(
+    frame: &mut Frame,
+    app: &App,
+    theme: &Theme,
+    area: ratatui::layout::Rect,
+    nav_area: ratatui::layout::Rect,
+) {
+    let Some(viewer) = &app.viewer else {
+        return;
+    };
+    let [header_area, text_area] =
+        Layout::vertical([Constraint::Length(2), Constraint::Min(0)]).areas(area);
+
+    let header = match &viewer.metadata {
+        Some(metadata) => metadata_summary(metadata),
+        None => format!("{} words", viewer.word_count()),
+    };
+    frame.render_widget(
+        Paragraph::new(header).style(Style::default().fg(theme.info)),
+        header_area,
+    );
+
+    let mut text_block = Block::default()
         .borders(Borders::ALL)
         .title(Span::styled(
-            " Session Ended ",
+            format!(" {} ", viewer.name),
             Style::default()
-                .fg(TITLE_COLOR)
+                .fg(theme.title)
                 .add_modifier(Modifier::BOLD),
         ))
-        .border_style(Style::default().fg(NORMAL_BORDER_COLOR))
+        .border_style(Style::default().fg(theme.focus_border))
         .border_type(ratatui::widgets::BorderType::Rounded)
-        .style(Style::default().fg(SELECTED_ITEM_FG));
+        .style(Style::default().fg(theme.selected_item_fg));
+    let inner_text_area = text_block.inner(text_area);
+    viewer
+        .viewport
+        .set((inner_text_area.width, inner_text_area.height));
 
-    frame.render_widget(block.clone(), area);
-    let inner_summary_area = block.inner(area);
+    let layout = viewer.layout();
+    let scroll = viewer.scroll.min(viewer.max_scroll());
+    let last_row = (scroll + viewer.page_height()).min(layout.lines.len());
+    text_block = text_block.title_bottom(
+        Line::from(format!(
+            " {}-{} of {} · {}% ",
+            scroll + 1,
+            last_row,
+            layout.lines.len(),
+            viewer.scroll_percent()
+        ))
+        .right_aligned(),
+    );
+    if !viewer.query.is_empty() {
+        let search_status = if viewer.matches.is_empty() {
+            format!(" No matches for \"{}\" ", viewer.query)
+        } else {
+            format!(
+                " Match {} of {} for \"{}\" ",
+                viewer.current_match + 1,
+                viewer.matches.len(),
+                viewer.query
+            )
+        };
+        text_block = text_block.title_bottom(Line::from(search_status).left_aligned());
+    }
+    frame.render_widget(text_block, text_area);
+
+    let lines: Vec<Line> = layout
+        .lines
+        .iter()
+        .skip(scroll)
+        .take(viewer.page_height())
+        .map(|line| {
+            highlighted_line(
+                &viewer.text,
+                line,
+                &viewer.matches,
+                viewer.current_match,
+                theme,
+            )
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines), inner_text_area);
+
+    if viewer.max_scroll() > 0 {
+        let mut scrollbar_state = ScrollbarState::new(viewer.max_scroll()).position(scroll);
+        frame.render_stateful_widget(
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None)
+                .style(Style::default().fg(theme.accent)),
+            text_area.inner(Margin {
+                vertical: 1,
+                horizontal: 0,
+            }),
+            &mut scrollbar_state,
+        );
+    }
+
+    if let Some(input) = &viewer.search_input {
+        let prompt = format!("/{}", input);
+        frame.set_cursor_position(Position::new(
+            nav_area.x + prompt.width() as u16,
+            nav_area.y,
+        ));
+        frame.render_widget(
+            Paragraph::new(prompt).style(Style::default().fg(theme.selected_item_fg)),
+            nav_area,
+        );
+    } else {
+        let nav_hint = "  [j/k] scroll  [Space/b] page  [g/G] top/bottom  [/] search  [n/N] next/prev  [c] continue writing  [Esc] back  ";
+        frame.render_widget(
+            Paragraph::new(nav_hint)
+                .alignment(Alignment::Center)
+                .style(Style::default().fg(theme.info)),
+            nav_area,
+        );
+    }
+}
+
+fn render_session_browser(
+    frame: &mut Frame,
+    app: &App,
+    theme: &Theme,
+    area: ratatui::layout::Rect,
+    nav_area: ratatui::layout::Rect,
+) {
+    let Some(browser) = &app.browser else {
+        return;
+    };
+    let [list_area, preview_area] =
+        Layout::horizontal([Constraint::Percentage(50), Constraint::Min(0)]).areas(area);
+
+    let order = match (browser.sort_by, browser.ascending) {
+        (SortBy::Date, false) => "newest first",
+        (SortBy::Date, true) => "oldest first",
+        (SortBy::Words, false) => "most words first",
+        (SortBy::Words, true) => "fewest words first",
+        (SortBy::Duration, false) => "longest first",
+        (SortBy::Duration, true) => "shortest first",
+    };
+    let list_block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(
+            format!(
+                " {} ({}) · by {} · {} ",
+                if browser.trash { "Trash" } else { "Sessions" },
+                browser.sessions.len(),
+                browser.grouping,
+                order
+            ),
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .border_style(Style::default().fg(theme.focus_border))
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .style(Style::default().fg(theme.selected_item_fg));
+
+    let mut items: Vec<ListItem> = browser
+        .rows
+        .iter()
+        .map(|row| match row {
+            BrowserRow::Header {
+                label,
+                sessions,
+                words,
+            } => ListItem::new(format!(
+                "{} · {} session{} · {} words",
+                label,
+                sessions,
+                if *sessions == 1 { "" } else { "s" },
+                words
+            ))
+            .style(
+                Style::default()
+                    .fg(theme.title)
+                    .add_modifier(Modifier::BOLD),
+            ),
+            BrowserRow::Session(index) => {
+                let entry = &browser.sessions[*index];
+                let saved_at = entry.saved_at().map_or_else(
+                    || "----------------".to_string(),
+                    |at| at.format("%Y-%m-%d %H:%M").to_string(),
+                );
+                let (words, duration) = entry.metadata.as_ref().map_or((0, 0), |metadata| {
+                    (metadata.word_count, metadata.duration_secs)
+                });
+                let name = storage::untrashed_name(&entry.name).unwrap_or(&entry.name);
+                let title = entry
+                    .metadata
+                    .as_ref()
+                    .and_then(|metadata| metadata.title.as_ref())
+                    .map_or_else(String::new, |title| format!("  “{}”", title));
+                ListItem::new(format!(
+                    "  {}  {:>6} words  {}  {}{}",
+                    saved_at,
+                    words,
+                    format_duration(Duration::from_secs(duration)),
+                    name,
+                    title
+                ))
+                .style(Style::default().fg(theme.file_item))
+            }
+        })
+        .collect();
+    if items.is_empty() {
+        let message = if browser.trash {
+            "The trash is empty"
+        } else {
+            "There's no saved session yet"
+        };
+        items.push(ListItem::new(message).style(Style::default().fg(theme.info)));
+    }
+    let list = List::new(items)
+        .block(list_block)
+        .highlight_symbol("> ")
+        .highlight_style(
+            Style::default()
+                .fg(theme.selected_item_fg)
+                .bg(theme.selected_item_bg)
+                .add_modifier(Modifier::BOLD),
+        );
+    frame.render_stateful_widget(list, list_area, &mut browser.list_state.borrow_mut());
+
+    let preview_block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(
+            " Preview ",
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .border_style(Style::default().fg(theme.normal_border))
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .style(Style::default().fg(theme.selected_item_fg));
+    let inner_preview_area = preview_block.inner(preview_area);
+    frame.render_widget(preview_block, preview_area);
+    if let Some(preview) = &browser.preview {
+        let mut header = vec![Line::from(match &preview.metadata {
+            Some(metadata) => metadata_summary(metadata),
+            None => format!("{} words", preview.text.split_whitespace().count()),
+        })];
+        // A daily page lists its sessions, each with its own word count
+        for session in &preview.page_sessions {
+            header.push(Line::from(format!(
+                "  {}  {:>6} words",
+                session.started_at.format("%H:%M"),
+                session.word_count
+            )));
+        }
+        let header_height = (header.len() as u16 + 1).min(inner_preview_area.height / 2);
+        let [header_area, text_area] =
+            Layout::vertical([Constraint::Length(header_height), Constraint::Min(0)])
+                .areas(inner_preview_area);
+        frame.render_widget(
+            Paragraph::new(header).style(Style::default().fg(theme.info)),
+            header_area,
+        );
+        frame.render_widget(
+            Paragraph::new(preview.text.as_str())
+                .wrap(Wrap { trim: true })
+                .style(Style::default().fg(theme.selected_item_fg)),
+            text_area,
+        );
+    }
+
+    let nav_hint = if browser.trash {
+        "  [j/k] move  [g] group  [s] sort  [r] reverse  [Enter] open  [u] restore  [d] delete for good  [Tab] sessions  [Esc] back  "
+    } else {
+        "  [j/k] move  [g] group  [s] sort  [r] reverse  [Enter] open  [n] rename  [t] title  [c] copy  [d] delete  [Tab] trash  [Esc] back  "
+    };
+    frame.render_widget(
+        Paragraph::new(nav_hint)
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(theme.info)),
+        nav_area,
+    );
+}
 
-    let summary_lines = vec![
+fn render_search(
+    frame: &mut Frame,
+    app: &App,
+    theme: &Theme,
+    area: ratatui::layout::Rect,
+    nav_area: ratatui::layout::Rect,
+) {
+    let Some(search) = &app.search else {
+        return;
+    };
+    let [input_area, body_area] =
+        Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(area);
+    let [results_area, preview_area] =
+        Layout::horizontal([Constraint::Percentage(45), Constraint::Min(0)]).areas(body_area);
+
+    let input_block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(
+            " Search all sessions ",
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .title_bottom(
+            Line::from(if search.regex {
+                " regex [Tab] "
+            } else {
+                " words [Tab] "
+            })
+            .right_aligned(),
+        )
+        .border_style(Style::default().fg(theme.focus_border))
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .style(Style::default().fg(theme.selected_item_fg));
+    let inner_input_area = input_block.inner(input_area);
+    frame.render_widget(input_block, input_area);
+    frame.render_widget(Paragraph::new(search.query.as_str()), inner_input_area);
+    frame.set_cursor_position(Position::new(
+        inner_input_area.x + search.query.width() as u16,
+        inner_input_area.y,
+    ));
+
+    let results_title = match search.hits.len() {
+        MAX_RESULTS => format!(" {}+ sessions ", MAX_RESULTS),
+        1 => " 1 session ".to_string(),
+        count => format!(" {} sessions ", count),
+    };
+    let results_block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(
+            results_title,
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .border_style(Style::default().fg(theme.normal_border))
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .style(Style::default().fg(theme.selected_item_fg));
+    let highlight = Style::default()
+        .fg(theme.selected_item_fg)
+        .bg(theme.accent)
+        .add_modifier(Modifier::BOLD);
+    let mut items: Vec<ListItem> = search
+        .hits
+        .iter()
+        .map(|hit| {
+            let saved_at = hit
+                .saved_at
+                .map_or_else(String::new, |at| at.format("%Y-%m-%d %H:%M  ").to_string());
+            let snippet = &hit.snippet;
+            ListItem::new(vec![
+                Line::from(Span::styled(
+                    format!("{}{}", saved_at, hit.name),
+                    Style::default().fg(theme.file_item),
+                )),
+                Line::from(vec![
+                    Span::raw("  "),
+                    Span::raw(&snippet[..hit.snippet_match.start]),
+                    Span::styled(&snippet[hit.snippet_match.clone()], highlight),
+                    Span::raw(&snippet[hit.snippet_match.end..]),
+                ]),
+            ])
+        })
+        .collect();
+    if items.is_empty() {
+        let message = match &search.error {
+            Some(error) => error.lines().last().unwrap_or_default().to_string(),
+            None if search.query.trim().is_empty() => "Type to search every session".to_string(),
+            None => "No session matches".to_string(),
+        };
+        items.push(ListItem::new(message).style(Style::default().fg(theme.info)));
+    }
+    let list = List::new(items)
+        .block(results_block)
+        .highlight_symbol("> ")
+        .highlight_style(
+            Style::default()
+                .bg(theme.selected_item_bg)
+                .add_modifier(Modifier::BOLD),
+        );
+    frame.render_stateful_widget(list, results_area, &mut search.list_state.borrow_mut());
+
+    let preview_block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(
+            " Preview ",
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .border_style(Style::default().fg(theme.normal_border))
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .style(Style::default().fg(theme.selected_item_fg));
+    let inner_preview_area = preview_block.inner(preview_area);
+    frame.render_widget(preview_block, preview_area);
+    if let Some(hit) = search.selected()
+        && let Some(text) = app
+            .search_index
+            .as_ref()
+            .and_then(|index| index.text(&hit.name))
+    {
+        // Start a few rows above the first match, so it is seen in context
+        let layout = TextLayout::new(text, inner_preview_area.width);
+        let first_row = hit
+            .matches
+            .first()
+            .map_or(0, |m| layout.line_index(m.start));
+        let lines: Vec<Line> = layout
+            .lines
+            .iter()
+            .skip(first_row.saturating_sub(inner_preview_area.height as usize / 3))
+            .take(inner_preview_area.height as usize)
+            .map(|line| highlighted_line(text, line, &hit.matches, 0, theme))
+            .collect();
+        frame.render_widget(Paragraph::new(lines), inner_preview_area);
+    }
+
+    let nav_hint = "  [Type] search  [Up/Down] move  [Tab] words/regex  [Enter] open  [Esc] back  ";
+    frame.render_widget(
+        Paragraph::new(nav_hint)
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(theme.info)),
+        nav_area,
+    );
+}
+
+// One row of `text`, with the `matches` on it highlighted and the one at
+// `current` standing out.
+fn highlighted_line<'a>(
+    text: &'a str,
+    line: &VisualLine,
+    matches: &[Range<usize>],
+    current: usize,
+    theme: &Theme,
+) -> Line<'a> {
+    let first = matches.partition_point(|m| m.end <= line.start);
+    let mut spans = Vec::new();
+    let mut position = line.start;
+    for (i, Range { start, end }) in matches.iter().enumerate().skip(first) {
+        if *start >= line.end {
+            break;
+        }
+        let (start, end) = ((*start).max(line.start), (*end).min(line.end));
+        if start > position {
+            spans.push(Span::raw(&text[position..start]));
+        }
+        let style = if i == current {
+            Style::default()
+                .fg(theme.selected_item_fg)
+                .bg(theme.accent)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default()
+                .fg(theme.selected_item_fg)
+                .bg(theme.selected_item_bg)
+        };
+        spans.push(Span::styled(&text[start..end], style));
+        position = end;
+    }
+    if position < line.end {
+        spans.push(Span::raw(&text[position..line.end]));
+    }
+    Line::from(spans)
+}
+
+// One line about a saved session, e.g.
+// `Timed · 2025-06-12 07:30 · 24:50 · 612 words · 24.6 WPM`.
+fn metadata_summary(metadata: &SessionMetadata) -> String {
+    let mode = match metadata.mode {
+        SessionMode::Timed => "Timed",
+        SessionMode::OpenEnded => "Open-ended",
+        SessionMode::WordGoal => "Word goal",
+        SessionMode::Sprint => "Sprint",
+        SessionMode::Draft => "Recovered draft",
+        SessionMode::DailyPage => "Daily page",
+        SessionMode::Unknown => "Session",
+    };
+    let mut parts = Vec::new();
+    if let Some(title) = &metadata.title {
+        parts.push(format!("“{}”", title));
+    }
+    parts.push(mode.to_string());
+    if let Some(started_at) = metadata.started_at {
+        parts.push(started_at.format("%Y-%m-%d %H:%M").to_string());
+    }
+    if metadata.duration_secs > 0 {
+        parts.push(format_duration(Duration::from_secs(metadata.duration_secs)));
+    }
+    if let Some(goal) = metadata.word_goal {
+        parts.push(format!("{}/{} words", metadata.word_count, goal));
+    } else {
+        parts.push(format!("{} words", metadata.word_count));
+    }
+    if metadata.wpm > 0.0 {
+        parts.push(format!("{:.1} WPM", metadata.wpm));
+    }
+    parts.join(" · ")
+}
+
+// `mm:ss`, with minutes going past 59 for long sessions.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
+
+fn render_sprint_break(
+    frame: &mut Frame,
+    app: &App,
+    theme: &Theme,
+    area: ratatui::layout::Rect,
+    nav_area: ratatui::layout::Rect,
+) {
+    let Some(series) = &app.sprint else {
+        return;
+    };
+    let time_left = series.break_time_left().as_secs();
+    let mut lines = vec![
         Line::from(vec![Span::styled(
-            "Freewrite Done!",
+            format!(
+                "Sprint {} of {} done!",
+                series.completed.len(),
+                series.sprint_count
+            ),
             Style::default()
-                .fg(SUCCESS_COLOR)
+                .fg(theme.success)
                 .add_modifier(Modifier::BOLD),
         )]),
         Line::from(vec![Span::raw("")]),
+    ];
+    if let Some(last) = series.completed.last() {
+        lines.push(Line::from(vec![
+            Span::raw(format!(
+                "{} words at {:.2} word/minutes",
+                last.word_count, last.wpm
+            ))
+            .style(Style::default().fg(theme.info)),
+        ]));
+    }
+    lines.extend([
         Line::from(vec![
-            Span::raw(format!("Your writing has been saved to:"))
-                .style(Style::default().fg(INFO_COLOR)),
+            Span::raw(format!("Words so far: {}", series.total_words()))
+                .style(Style::default().fg(theme.info)),
         ]),
+        Line::from(vec![Span::raw("")]),
+        Line::from(vec![Span::styled(
+            format!(
+                "Take a break. Sprint {} starts in {:02}:{:02}",
+                series.current_sprint(),
+                time_left / 60,
+                time_left % 60
+            ),
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )]),
+    ]);
+
+    render_full_screen(
+        frame,
+        theme,
+        " Break ",
+        lines,
+        area,
+        nav_area,
+        "  [Enter] start the next sprint now  [Esc] end the series  [q] quit  ",
+    );
+}
+
+fn render_sprint_series_end(
+    frame: &mut Frame,
+    theme: &Theme,
+    series: &SprintSeries,
+    area: ratatui::layout::Rect,
+    nav_area: ratatui::layout::Rect,
+) {
+    let total_secs = series.total_duration().as_secs();
+    let mut lines = vec![
         Line::from(vec![Span::styled(
-            format!("{}", final_filename),
-            Style::default().fg(FOCUS_BORDER_COLOR),
+            format!(
+                "Sprint series done: {} of {} sprints",
+                series.completed.len(),
+                series.sprint_count
+            ),
+            Style::default()
+                .fg(theme.success)
+                .add_modifier(Modifier::BOLD),
         )]),
         Line::from(vec![Span::raw("")]),
         Line::from(vec![
-            Span::raw(format!("Word Count: {}", word_count)).style(Style::default().fg(INFO_COLOR)),
-        ]),
-        Line::from(vec![
-            Span::raw(format!("Typing Speed: {:.2} word/minutes", wpm))
-                .style(Style::default().fg(INFO_COLOR)),
+            Span::raw(format!(
+                "Word Count: {} | Writing Time: {:02}:{:02} | Typing Speed: {:.2} word/minutes",
+                series.total_words(),
+                total_secs / 60,
+                total_secs % 60,
+                series.wpm()
+            ))
+            .style(Style::default().fg(theme.info)),
         ]),
         Line::from(vec![Span::raw("")]),
+    ];
+    for (i, sprint) in series.completed.iter().enumerate() {
+        lines.push(Line::from(vec![
+            Span::raw(format!(
+                "Sprint {}: {} words, {:.2} word/minutes  ",
+                i + 1,
+                sprint.word_count,
+                sprint.wpm
+            ))
+            .style(Style::default().fg(theme.info)),
+            Span::styled(
+                sprint.final_filename.clone(),
+                Style::default().fg(theme.focus_border),
+            ),
+        ]));
+    }
+    lines.extend([
+        Line::from(vec![Span::raw("")]),
         Line::from(vec![Span::styled(
             "Press [Enter] to go back to main menu...",
             Style::default()
-                .fg(ACCENT_COLOR)
+                .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         )]),
-    ];
+    ]);
 
-    let paragraph = Paragraph::new(summary_lines)
+    render_full_screen(
+        frame,
+        theme,
+        " Sprint Series Ended ",
+        lines,
+        area,
+        nav_area,
+        "  [Enter] back to menu  [q] quit  ",
+    );
+}
+
+// A bordered screen filling `area` with centered `lines`, and `nav_hint` below.
+fn render_full_screen(
+    frame: &mut Frame,
+    theme: &Theme,
+    title: &str,
+    lines: Vec<Line>,
+    area: ratatui::layout::Rect,
+    nav_area: ratatui::layout::Rect,
+    nav_hint: &str,
+) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(
+            title,
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .border_style(Style::default().fg(theme.normal_border))
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .style(Style::default().fg(theme.selected_item_fg));
+
+    frame.render_widget(block.clone(), area);
+    let inner_area = block.inner(area);
+
+    let paragraph = Paragraph::new(lines)
         .alignment(Alignment::Center)
-        .style(Style::default().fg(SELECTED_ITEM_FG));
-    frame.render_widget(paragraph, inner_summary_area);
+        .style(Style::default().fg(theme.selected_item_fg));
+    frame.render_widget(paragraph, inner_area);
 
-    let nav_hint_end = format!("  [Enter] back to menu  [q] quit  ");
-    let nav_paragraph_end = Paragraph::new(nav_hint_end)
+    let nav_paragraph = Paragraph::new(nav_hint)
         .alignment(Alignment::Center)
-        .style(Style::default().fg(INFO_COLOR));
-    frame.render_widget(nav_paragraph_end, nav_area);
+        .style(Style::default().fg(theme.info));
+    frame.render_widget(nav_paragraph, nav_area);
+}
+
+fn render_custom_duration_input_popup(frame: &mut Frame, app: &App, config: &Config) {
+    render_input_popup(
+        frame,
+        &config.theme,
+        " Custom Duration (minutes) ",
+        &format!(
+            "Enter duration (default {}, 0 = open-ended):",
+            config.default_session_duration_mins
+        ),
+        &app.custom_duration_input_text,
+        55,
+    );
+}
+
+fn render_custom_word_goal_input_popup(frame: &mut Frame, app: &App, theme: &Theme) {
+    render_input_popup(
+        frame,
+        theme,
+        " Custom Word Goal ",
+        &format!("Enter a word count (default {}):", DEFAULT_WORD_GOAL),
+        &app.custom_word_goal_input_text,
+        55,
+    );
+}
+
+fn render_alternate_path_input_popup(frame: &mut Frame, app: &App, theme: &Theme) {
+    render_input_popup(
+        frame,
+        theme,
+        " Save To ",
+        "Enter a file or directory path:",
+        &app.alternate_path_input_text,
+        70,
+    );
+}
+
+fn render_open_session_popup(frame: &mut Frame, app: &App, theme: &Theme, name: &str) {
+    let saved_at = app
+        .recent_files
+        .iter()
+        .find(|entry| entry.name == name)
+        .and_then(|entry| entry.saved_at());
+    let mut lines = vec![Line::from(Span::styled(
+        name.to_string(),
+        Style::default().fg(theme.focus_border),
+    ))];
+    if let Some(saved_at) = saved_at {
+        lines.push(Line::from(Span::styled(
+            format!("Last written {}", saved_at.format("%A, %Y-%m-%d %H:%M")),
+            Style::default().fg(theme.info),
+        )));
+    }
+    let continue_hint = match app.continue_mode {
+        ContinueMode::Append => "Continuing writes the new text into this file.",
+        ContinueMode::Version => "Continuing saves a new version next to this file.",
+    };
+    lines.push(Line::from(Span::styled(
+        continue_hint,
+        Style::default().fg(theme.info),
+    )));
+    render_message_popup(
+        frame,
+        theme,
+        "Open Session",
+        lines,
+        "[Enter] View [c] Continue writing [Esc] Cancel",
+    );
+}
+
+fn render_session_action_popup(
+    frame: &mut Frame,
+    app: &App,
+    theme: &Theme,
+    action: SessionAction,
+    name: &str,
+) {
+    let name = storage::untrashed_name(name).unwrap_or(name);
+    if action.takes_input() {
+        let (title, prompt) = match action {
+            SessionAction::Rename => (" Rename ", format!("New name for {}:", name)),
+            _ => (" Title ", format!("Title of {} (empty for none):", name)),
+        };
+        render_input_popup(
+            frame,
+            theme,
+            title,
+            app.session_action_error.as_deref().unwrap_or(&prompt),
+            &app.session_action_input,
+            70,
+        );
+        return;
+    }
+    let (title, question, consequence) = match action {
+        SessionAction::Duplicate => (
+            "Duplicate",
+            "Make a copy of",
+            "The copy is saved next to it.",
+        ),
+        SessionAction::Trash => (
+            "Delete",
+            "Move to the trash",
+            "You can restore it from the trash in the browser ([Tab]).",
+        ),
+        SessionAction::Restore => (
+            "Restore",
+            "Restore",
+            "It goes back with your other sessions.",
+        ),
+        _ => (
+            "Delete for Good",
+            "Permanently delete",
+            "This can't be undone.",
+        ),
+    };
+    let mut lines = vec![
+        Line::from(Span::styled(
+            format!("{} {}?", question, name),
+            Style::default().fg(theme.info),
+        )),
+        Line::from(Span::styled(consequence, Style::default().fg(theme.info))),
+    ];
+    if let Some(error) = &app.session_action_error {
+        lines.push(Line::from(Span::raw("")));
+        lines.push(Line::from(Span::styled(
+            error.clone(),
+            Style::default().fg(theme.focus_border),
+        )));
+    }
+    render_message_popup(frame, theme, title, lines, "[y] Yes [n] No");
 }
 
-fn render_custom_duration_input_popup(frame: &mut Frame, app: &App) {
+fn render_input_popup(
+    frame: &mut Frame,
+    theme: &Theme,
+    title: &str,
+    prompt: &str,
+    input: &str,
+    popup_width: u16,
+) {
     let size = frame.area();
 
     // Create centered popup
-    let popup_width = 55;
+    let popup_width = popup_width.min(size.width);
     let popup_height = 11;
     let popup_x = (size.width.saturating_sub(popup_width)) / 2;
     let popup_y = (size.height.saturating_sub(popup_height)) / 2;
@@ -417,14 +1480,14 @@ fn render_custom_duration_input_popup(frame: &mut Frame, app: &App) {
     let popup_block = Block::default()
         .borders(Borders::ALL)
         .title(Span::styled(
-            " Custom Duration (minutes) ",
+            title.to_string(),
             Style::default()
-                .fg(TITLE_COLOR)
+                .fg(theme.title)
                 .add_modifier(Modifier::BOLD),
         ))
-        .border_style(Style::default().fg(FOCUS_BORDER_COLOR))
+        .border_style(Style::default().fg(theme.focus_border))
         .border_type(ratatui::widgets::BorderType::Rounded)
-        .style(Style::default().fg(SELECTED_ITEM_FG));
+        .style(Style::default().fg(theme.selected_item_fg));
 
     let inner_area = popup_block.inner(popup_area);
     frame.render_widget(popup_block, popup_area);
@@ -450,12 +1513,9 @@ fn render_custom_duration_input_popup(frame: &mut Frame, app: &App) {
         ])
         .split(sections[0]);
 
-    let prompt = Paragraph::new(format!(
-        "Enter duration (default {}):",
-        DEFAULT_SESSION_DURATION_MINS
-    ))
-    .style(Style::default().fg(INFO_COLOR))
-    .alignment(Alignment::Center);
+    let prompt = Paragraph::new(prompt.to_string())
+        .style(Style::default().fg(theme.info))
+        .alignment(Alignment::Center);
     frame.render_widget(prompt, prompt_layout[1]);
 
     // Render input section
@@ -468,16 +1528,16 @@ fn render_custom_duration_input_popup(frame: &mut Frame, app: &App) {
         ])
         .split(sections[1]);
 
-    let input_field = Paragraph::new(app.custom_duration_input_text.as_str())
+    let input_field = Paragraph::new(input)
         .style(
             Style::default()
-                .fg(SELECTED_ITEM_FG)
+                .fg(theme.selected_item_fg)
                 .add_modifier(Modifier::BOLD),
         )
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_style(Style::default().fg(NORMAL_BORDER_COLOR))
+                .border_style(Style::default().fg(theme.normal_border))
                 .border_type(ratatui::widgets::BorderType::Plain),
         );
     frame.render_widget(input_field, input_layout[1]);
@@ -494,14 +1554,209 @@ fn render_custom_duration_input_popup(frame: &mut Frame, app: &App) {
     let hint = Paragraph::new("[Enter] Confirm [Esc] Cancel")
         .style(
             Style::default()
-                .fg(INFO_COLOR)
+                .fg(theme.info)
                 .add_modifier(Modifier::ITALIC),
         )
         .alignment(Alignment::Center);
     frame.render_widget(hint, hint_layout[0]);
 
     // Set cursor position
-    let cursor_x = input_layout[1].x + 1 + app.custom_duration_input_text.len() as u16;
+    let cursor_x = input_layout[1].x + 1 + input.chars().count() as u16;
     let cursor_y = input_layout[1].y + 1;
     frame.set_cursor_position(Position::new(cursor_x, cursor_y));
 }
+
+fn render_notice_popup(frame: &mut Frame, theme: &Theme, title: &str, messages: &[String]) {
+    // The first message is the summary, the rest are listed as details
+    let mut lines = Vec::new();
+    if let Some((summary, details)) = messages.split_first() {
+        lines.push(Line::from(Span::styled(
+            summary.clone(),
+            Style::default().fg(theme.info),
+        )));
+        lines.push(Line::from(Span::raw("")));
+        for detail in details {
+            lines.push(Line::from(Span::styled(
+                format!("- {}", detail),
+                Style::default().fg(theme.selected_item_fg),
+            )));
+        }
+    }
+    render_message_popup(frame, theme, title, lines, "[Enter] Continue [q] Quit");
+}
+
+fn render_migration_prompt_popup(frame: &mut Frame, app: &App, theme: &Theme) {
+    let legacy_dir = app
+        .legacy_sessions_dir
+        .as_ref()
+        .map(|dir| dir.display().to_string())
+        .unwrap_or_default();
+    let lines = vec![
+        Line::from(Span::styled(
+            "Found sessions saved by an older version in:",
+            Style::default().fg(theme.info),
+        )),
+        Line::from(Span::styled(
+            legacy_dir,
+            Style::default().fg(theme.focus_border),
+        )),
+        Line::from(Span::raw("")),
+        Line::from(Span::styled(
+            "Move them to the sessions directory?",
+            Style::default().fg(theme.info),
+        )),
+        Line::from(Span::styled(
+            app.sessions_dir.display().to_string(),
+            Style::default().fg(theme.focus_border),
+        )),
+    ];
+    render_message_popup(
+        frame,
+        theme,
+        "Move Sessions",
+        lines,
+        "[y] Move [n] Keep where they are",
+    );
+}
+
+fn render_draft_recovery_popup(frame: &mut Frame, app: &App, theme: &Theme) {
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "These sessions were interrupted before they could be saved:",
+            Style::default().fg(theme.info),
+        )),
+        Line::from(Span::raw("")),
+    ];
+    for (i, draft) in app.orphaned_drafts.iter().enumerate() {
+        let style = if i == app.selected_draft_index {
+            Style::default()
+                .fg(theme.selected_item_fg)
+                .bg(theme.selected_item_bg)
+                .add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.file_item)
+        };
+        lines.push(Line::from(Span::styled(
+            format!(
+                "{}{}  {} words  {}",
+                if i == app.selected_draft_index {
+                    "> "
+                } else {
+                    "  "
+                },
+                draft.started_at.format("%Y-%m-%d %H:%M"),
+                draft.word_count(),
+                draft.first_line()
+            ),
+            style,
+        )));
+    }
+    render_message_popup(
+        frame,
+        theme,
+        "Recover Drafts",
+        lines,
+        "[r] Restore [s] Save [d] Discard [Esc] Later",
+    );
+}
+
+fn render_quit_confirm_popup(frame: &mut Frame, app: &App, theme: &Theme) {
+    let lines = vec![
+        Line::from(Span::styled(
+            "This session hasn't been saved yet.",
+            Style::default().fg(theme.info),
+        )),
+        Line::from(Span::styled(
+            format!(
+                "{} words will be lost if you discard it.",
+                app.get_word_count()
+            ),
+            Style::default().fg(theme.info),
+        )),
+    ];
+    render_message_popup(
+        frame,
+        theme,
+        "Quit",
+        lines,
+        "[s] Save and quit [d] Discard and quit [k] Keep writing",
+    );
+}
+
+fn render_save_error_popup(frame: &mut Frame, app: &App, theme: &Theme) {
+    let mut lines = vec![
+        Line::from(Span::styled(
+            "Your session couldn't be saved:",
+            Style::default().fg(theme.info),
+        )),
+        Line::from(Span::styled(
+            app.save_error
+                .as_ref()
+                .map(|e| e.to_string())
+                .unwrap_or_default(),
+            Style::default().fg(theme.focus_border),
+        )),
+        Line::from(Span::raw("")),
+        Line::from(Span::styled(
+            "Nothing is lost: the text is still here and in its draft.",
+            Style::default().fg(theme.info),
+        )),
+    ];
+    if let Some(status) = &app.save_error_status {
+        lines.push(Line::from(Span::styled(
+            status.clone(),
+            Style::default().fg(theme.success),
+        )));
+    }
+    render_message_popup(
+        frame,
+        theme,
+        "Save Failed",
+        lines,
+        "[r] Retry [a] Save elsewhere [c] Copy to clipboard [Esc] Keep writing",
+    );
+}
+
+// Centered popup sized to its content, with an italic key hint at the bottom.
+fn render_message_popup(
+    frame: &mut Frame,
+    theme: &Theme,
+    title: &str,
+    mut lines: Vec<Line>,
+    hint: &str,
+) {
+    let size = frame.area();
+
+    let popup_width = size.width.saturating_sub(4).min(80);
+    let popup_height = (lines.len() as u16 + 4).min(size.height);
+    let popup_x = (size.width.saturating_sub(popup_width)) / 2;
+    let popup_y = (size.height.saturating_sub(popup_height)) / 2;
+    let popup_area = Rect::new(popup_x, popup_y, popup_width, popup_height);
+
+    frame.render_widget(Clear, popup_area);
+
+    let popup_block = Block::default()
+        .borders(Borders::ALL)
+        .title(Span::styled(
+            format!(" {} ", title),
+            Style::default()
+                .fg(theme.title)
+                .add_modifier(Modifier::BOLD),
+        ))
+        .border_style(Style::default().fg(theme.focus_border))
+        .border_type(ratatui::widgets::BorderType::Rounded)
+        .style(Style::default().fg(theme.selected_item_fg));
+
+    lines.push(Line::from(Span::raw("")));
+    lines.push(Line::from(Span::styled(
+        hint.to_string(),
+        Style::default()
+            .fg(theme.info)
+            .add_modifier(Modifier::ITALIC),
+    )));
+
+    let paragraph = Paragraph::new(lines)
+        .block(popup_block)
+        .wrap(Wrap { trim: false });
+    frame.render_widget(paragraph, popup_area);
+}
@@ -0,0 +1,243 @@
+use std::{cell::Cell, ops::Range};
+
+use crate::layout::TextLayout;
+use crate::metadata::SessionMetadata;
+
+// Full-screen, read-only view of a saved session, opened from the Recent
+// panel. The text is wrapped with the same `TextLayout` as the writing area
+// and scrolled by screen rows; the size of the text area is only known when
+// rendering, so it is kept from the last frame.
+
+pub struct SessionViewer {
+    pub name: String,
+    pub text: String,
+    pub metadata: Option<SessionMetadata>,
+    /// First row shown.
+    pub scroll: usize,
+    /// Width and height of the text area at the last render.
+    pub viewport: Cell<(u16, u16)>,
+    /// Query being typed, while the search prompt is open.
+    pub search_input: Option<String>,
+    /// The last search, and the byte ranges of the text it matched.
+    pub query: String,
+    pub matches: Vec<Range<usize>>,
+    pub current_match: usize,
+}
+
+impl SessionViewer {
+    pub fn new(name: String, text: String, metadata: Option<SessionMetadata>) -> Self {
+        Self {
+            name,
+            text,
+            metadata,
+            scroll: 0,
+            viewport: Cell::new((80, 20)),
+            search_input: None,
+            query: String::new(),
+            matches: Vec::new(),
+            current_match: 0,
+        }
+    }
+
+    /// Shows the results of a search made elsewhere, starting at the first.
+    pub fn show_matches(&mut self, query: String, matches: Vec<Range<usize>>) {
+        self.query = query;
+        self.matches = matches;
+        self.current_match = 0;
+        self.scroll_to_match();
+    }
+
+    pub fn word_count(&self) -> usize {
+        self.text.split_whitespace().count()
+    }
+
+    /// The text wrapped to the text area, as it is drawn on screen.
+    pub fn layout(&self) -> TextLayout {
+        TextLayout::new(&self.text, self.viewport.get().0)
+    }
+
+    pub fn page_height(&self) -> usize {
+        (self.viewport.get().1 as usize).max(1)
+    }
+
+    /// Scroll position that shows the last row at the bottom of the screen.
+    pub fn max_scroll(&self) -> usize {
+        self.layout().lines.len().saturating_sub(self.page_height())
+    }
+
+    /// Moves the view by `rows`, down when positive.
+    pub fn scroll_by(&mut self, rows: isize) {
+        self.scroll = self
+            .scroll
+            .saturating_add_signed(rows)
+            .min(self.max_scroll());
+    }
+
+    pub fn page_down(&mut self) {
+        self.scroll_by(self.page_height().saturating_sub(1).max(1) as isize);
+    }
+
+    pub fn page_up(&mut self) {
+        self.scroll_by(-(self.page_height().saturating_sub(1).max(1) as isize));
+    }
+
+    pub fn scroll_to_top(&mut self) {
+        self.scroll = 0;
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = self.max_scroll();
+    }
+
+    /// How far through the text the view is, from 0 to 100.
+    pub fn scroll_percent(&self) -> usize {
+        match self.max_scroll() {
+            0 => 100,
+            max => self.scroll.min(max) * 100 / max,
+        }
+    }
+
+    /// Runs the query typed into the search prompt and jumps to the first
+    /// match at or after the top of the screen.
+    pub fn submit_search(&mut self) {
+        let Some(query) = self.search_input.take() else {
+            return;
+        };
+        self.matches = find_matches(&self.text, &query);
+        self.query = query;
+        let layout = self.layout();
+        self.current_match = self
+            .matches
+            .iter()
+            .position(|m| layout.line_index(m.start) >= self.scroll)
+            .unwrap_or(0);
+        self.scroll_to_match();
+    }
+
+    pub fn clear_search(&mut self) {
+        self.query.clear();
+        self.matches.clear();
+        self.current_match = 0;
+    }
+
+    pub fn next_match(&mut self) {
+        if !self.matches.is_empty() {
+            self.current_match = (self.current_match + 1) % self.matches.len();
+            self.scroll_to_match();
+        }
+    }
+
+    pub fn previous_match(&mut self) {
+        if !self.matches.is_empty() {
+            self.current_match = (self.current_match + self.matches.len() - 1) % self.matches.len();
+            self.scroll_to_match();
+        }
+    }
+
+    // Brings the current match on screen, a third of the way down when the
+    // view has to move.
+    fn scroll_to_match(&mut self) {
+        let Some(current) = self.matches.get(self.current_match) else {
+            return;
+        };
+        let row = self.layout().line_index(current.start);
+        if row < self.scroll || row >= self.scroll + self.page_height() {
+            self.scroll = row
+                .saturating_sub(self.page_height() / 3)
+                .min(self.max_scroll());
+        }
+    }
+}
+
+/// Byte ranges of `text` matching `query`, ignoring case.
+pub fn find_matches(text: &str, query: &str) -> Vec<Range<usize>> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut matches = Vec::new();
+    let mut search_from = 0;
+    for (start, _) in text.char_indices() {
+        if start < search_from {
+            continue;
+        }
+        if let Some(len) = match_len(&text[start..], &query) {
+            matches.push(start..start + len);
+            search_from = start + len;
+        }
+    }
+    matches
+}
+
+// Length in bytes of the start of `text` that equals `query` (lowercased).
+fn match_len(text: &str, query: &[char]) -> Option<usize> {
+    let mut query = query.iter();
+    let mut len = 0;
+    let mut pending = query.len();
+    for c in text.chars() {
+        if pending == 0 {
+            break;
+        }
+        for lower in c.to_lowercase() {
+            if query.next() != Some(&lower) {
+                return None;
+            }
+            pending -= 1;
+        }
+        len += c.len_utf8();
+    }
+    (pending == 0).then_some(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matched<'a>(text: &'a str, query: &str) -> Vec<&'a str> {
+        find_matches(text, query)
+            .into_iter()
+            .map(|range| &text[range])
+            .collect()
+    }
+
+    #[test]
+    fn matches_ignore_case() {
+        assert_eq!(
+            matched("Word, word and WORD", "word"),
+            ["Word", "word", "WORD"]
+        );
+        assert_eq!(matched("Élan et élan", "ÉLAN"), ["Élan", "élan"]);
+        assert!(find_matches("anything", "").is_empty());
+    }
+
+    #[test]
+    fn matches_dont_overlap() {
+        assert_eq!(find_matches("aaaa", "aa"), [0..2, 2..4]);
+    }
+
+    #[test]
+    fn ranges_cover_characters_whose_lowercase_is_longer() {
+        // `İ` lowercases to `i` and a combining dot, `K` (Kelvin) to `k` and
+        // `ẞ` to `ß`, each a different number of bytes than what it matches
+        let text = "İstanbul, 5 \u{212A}, STRAẞE";
+        assert_eq!(matched(text, "i̇stanbul"), ["İstanbul"]);
+        assert_eq!(matched(text, "İSTANBUL"), ["İstanbul"]);
+        assert_eq!(matched(text, "5 k"), ["5 \u{212A}"]);
+        assert_eq!(matched(text, "straße"), ["STRAẞE"]);
+        // A plain `i` is only part of what `İ` lowercases to
+        assert!(find_matches("İ", "i").is_empty());
+        assert!(find_matches("İ", "i\u{307}x").is_empty());
+    }
+
+    #[test]
+    fn ranges_are_on_character_boundaries() {
+        let text = "ßß straße STRASSE ẞ 日本語 naïve NAÏVE 👨‍👩‍👧 İi";
+        for query in ["ß", "ss", "ẞ", "本", "ï", "i", "İ", "👩", "e"] {
+            for range in find_matches(text, query) {
+                assert!(text.is_char_boundary(range.start), "{:?}", query);
+                assert!(text.is_char_boundary(range.end), "{:?}", query);
+            }
+        }
+        assert_eq!(matched(text, "ß"), ["ß", "ß", "ß", "ẞ"]);
+    }
+}